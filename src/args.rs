@@ -0,0 +1,506 @@
+use crate::complete::{complete_collections, complete_links, complete_tags};
+use abhyas::database::MAX_SNOOZE_DAYS;
+use abhyas::link::{LinkSort, LinkState, SelectionStrategy};
+use abhyas::sync::MergePolicy;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use clap_complete::engine::ArgValueCompleter;
+use clap_complete::Shell;
+use std::path::PathBuf;
+
+/// command line arguments, running without any subcommand opens the interactive menu
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// add links from the given file, one link per line
+    #[arg(long, value_name = "FILE_PATH")]
+    pub file: Option<PathBuf>,
+
+    /// add the links from --file to this collection, keeping the order of the file
+    #[arg(long, requires = "file", add = ArgValueCompleter::new(complete_collections))]
+    pub collection: Option<String>,
+
+    /// show how many links --file would add without changing the db
+    #[arg(long, requires = "file")]
+    pub dry_run: bool,
+
+    /// print the underlying causes of errors
+    #[arg(long, short, global = true)]
+    pub verbose: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// change completed and/or skipped links back to incomplete
+    Reset(ResetArgs),
+    /// search links by url, title, notes and tags, best matches first
+    Search(SearchArgs),
+    /// open the full-screen terminal interface
+    Tui,
+    /// write every link on its own line, the same format read by --file
+    Export(ExportArgs),
+    /// list links, optionally narrowed down by state, tag and collection
+    List(ListArgs),
+    /// show the next pending link, from a collection when given
+    Next(NextArgs),
+    /// add a single link
+    Add(AddArgs),
+    /// mark a link as completed
+    Done(LinkArgs),
+    /// mark a link as skipped
+    Skip(LinkArgs),
+    /// skip a link for a while, it goes back to the pool once the time is up
+    Snooze(SnoozeArgs),
+    /// record a failed attempt, failed links are served before pending ones
+    Fail(LinkArgs),
+    /// delete a link along with its history, tags and collection memberships
+    Delete(DeleteArgs),
+    /// show the total, completed, skipped and pending links count
+    Status(JsonArgs),
+    /// show the status, the progress of every collection and the recent activity
+    Stats(JsonArgs),
+    /// serve the db as a json api on localhost
+    Serve(ServeArgs),
+    /// mirror the db to text files that can be diffed and synced with git
+    #[command(subcommand)]
+    Sync(SyncCommand),
+    /// merge the links, tags, history and collections of another abhyas db file
+    Merge(MergeArgs),
+    /// print the shell completion script
+    Completions(CompletionsArgs),
+    /// practice a few links against the clock like a mock interview
+    Session(SessionArgs),
+    /// list past sessions, or show the links of one session
+    Sessions(SessionsArgs),
+    /// send a notification when today's goal or due reviews are outstanding
+    Remind(RemindArgs),
+    /// manage the events posted to the webhooks of the config
+    #[command(subcommand)]
+    Webhooks(WebhooksCommand),
+}
+
+#[derive(Args)]
+pub struct RemindArgs {
+    /// keep running and check every N minutes instead of checking once
+    #[arg(long, value_name = "MINUTES", value_parser = clap::value_parser!(u64).range(1..))]
+    pub every: Option<u64>,
+
+    /// print the reminder instead of running the notify command
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Subcommand)]
+pub enum WebhooksCommand {
+    /// retry the events that couldn't be delivered earlier
+    Flush,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum StrategyArg {
+    /// pending links in the order they were added
+    Next,
+    /// pending links in random order
+    Random,
+    /// links that aren't skipped, fewest solves first
+    LeastSolved,
+    /// completed links, the longest ago completed first
+    Review,
+}
+
+impl From<StrategyArg> for SelectionStrategy {
+    fn from(strategy: StrategyArg) -> Self {
+        match strategy {
+            StrategyArg::Next => SelectionStrategy::Next,
+            StrategyArg::Random => SelectionStrategy::Random,
+            StrategyArg::LeastSolved => SelectionStrategy::LeastSolved,
+            StrategyArg::Review => SelectionStrategy::Review,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct SessionArgs {
+    /// number of links to practice
+    #[arg(long, default_value_t = 3)]
+    pub count: usize,
+
+    /// time for the whole session
+    #[arg(long, default_value_t = 60)]
+    pub minutes: u32,
+
+    /// only pick links with this tag
+    #[arg(long, add = ArgValueCompleter::new(complete_tags))]
+    pub tag: Option<String>,
+
+    /// how the links are picked
+    #[arg(long, value_enum, default_value_t = StrategyArg::Next)]
+    pub strategy: StrategyArg,
+
+    /// open every link in the browser when its turn comes
+    #[arg(long)]
+    pub open: bool,
+}
+
+#[derive(Args)]
+pub struct SessionsArgs {
+    /// show the links of this session
+    pub id: Option<i64>,
+
+    #[command(flatten)]
+    pub output: JsonArgs,
+}
+
+#[derive(Args)]
+pub struct CompletionsArgs {
+    pub shell: Shell,
+
+    /// print a script that also completes links, tags and collections from the db
+    #[arg(long)]
+    pub dynamic: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum PolicyArg {
+    /// keep the higher solved count and the state of the side practiced last
+    Max,
+    /// count every completion in the history of either side once, keep the higher
+    /// count where there is no history, and the state of the side practiced last
+    Union,
+    /// keep the local counters and state
+    PreferLocal,
+    /// take the counters and state of the other db
+    PreferRemote,
+}
+
+impl From<PolicyArg> for MergePolicy {
+    fn from(policy: PolicyArg) -> Self {
+        match policy {
+            PolicyArg::Max => MergePolicy::Max,
+            PolicyArg::Union => MergePolicy::Union,
+            PolicyArg::PreferLocal => MergePolicy::PreferLocal,
+            PolicyArg::PreferRemote => MergePolicy::PreferRemote,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct MergeArgs {
+    /// the abhyas.db file to merge into the local db
+    #[arg(value_name = "OTHER_DB")]
+    pub other: PathBuf,
+
+    /// how to reconcile the solved count and state of links present in both
+    #[arg(long, value_enum, default_value_t = PolicyArg::Max)]
+    pub policy: PolicyArg,
+
+    /// show what would change without touching the db
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Subcommand)]
+pub enum SyncCommand {
+    /// write links.jsonl and collections.jsonl into the directory
+    Export {
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+    },
+    /// merge the files of the directory into the db, keeping the higher counters
+    /// and the history of both sides
+    Import {
+        #[arg(value_name = "DIR")]
+        dir: PathBuf,
+
+        /// show what would change without touching the db
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum StateArg {
+    Pending,
+    Completed,
+    Skipped,
+    Failed,
+}
+
+impl From<StateArg> for LinkState {
+    fn from(state: StateArg) -> Self {
+        match state {
+            StateArg::Pending => LinkState::Pending,
+            StateArg::Completed => LinkState::Completed,
+            StateArg::Skipped => LinkState::Skipped,
+            StateArg::Failed => LinkState::Failed,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum SortArg {
+    Added,
+    Link,
+    SolvedCount,
+    CompletedAt,
+    LeastRecentlyCompleted,
+    LeastSolved,
+    Random,
+}
+
+impl From<SortArg> for LinkSort {
+    fn from(sort: SortArg) -> Self {
+        match sort {
+            SortArg::Added => LinkSort::Added,
+            SortArg::Link => LinkSort::Link,
+            SortArg::SolvedCount => LinkSort::SolvedCount,
+            SortArg::CompletedAt => LinkSort::CompletedAt,
+            SortArg::LeastRecentlyCompleted => LinkSort::LeastRecentlyCompleted,
+            SortArg::LeastSolved => LinkSort::LeastSolved,
+            SortArg::Random => LinkSort::Random,
+        }
+    }
+}
+
+#[derive(Args)]
+pub struct JsonArgs {
+    /// print json instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct ListArgs {
+    /// only links in this state
+    #[arg(long, value_enum)]
+    pub state: Option<StateArg>,
+
+    /// only links with this tag
+    #[arg(long, add = ArgValueCompleter::new(complete_tags))]
+    pub tag: Option<String>,
+
+    /// only links that belong to this collection, in the order of the collection
+    #[arg(long, add = ArgValueCompleter::new(complete_collections))]
+    pub collection: Option<String>,
+
+    #[arg(long, value_enum, default_value_t = SortArg::Added)]
+    pub sort: SortArg,
+
+    /// maximum number of links to show
+    #[arg(long)]
+    pub limit: Option<usize>,
+
+    /// number of links to leave out from the start
+    #[arg(long, default_value_t = 0)]
+    pub offset: usize,
+
+    #[command(flatten)]
+    pub output: JsonArgs,
+}
+
+#[derive(Args)]
+pub struct NextArgs {
+    /// take the next link of this collection instead of the first pending link
+    #[arg(long, add = ArgValueCompleter::new(complete_collections))]
+    pub collection: Option<String>,
+
+    #[command(flatten)]
+    pub output: JsonArgs,
+}
+
+#[derive(Args)]
+pub struct AddArgs {
+    #[arg(required_unless_present = "from_clipboard")]
+    pub link: Option<String>,
+
+    /// add the link held in the clipboard, read with the paste_command of the config
+    #[arg(long, conflicts_with = "link")]
+    pub from_clipboard: bool,
+
+    /// check the link without adding it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub output: JsonArgs,
+}
+
+#[derive(Args)]
+pub struct LinkArgs {
+    #[arg(add = ArgValueCompleter::new(complete_links))]
+    pub link: String,
+
+    /// check the link without changing it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub output: JsonArgs,
+}
+
+#[derive(Args)]
+pub struct SnoozeArgs {
+    #[arg(add = ArgValueCompleter::new(complete_links))]
+    pub link: String,
+
+    /// how long to skip the link, in days like 7d or 7, or in weeks like 2w
+    #[arg(value_parser = parse_days)]
+    pub days: u32,
+
+    /// check the link without changing it
+    #[arg(long)]
+    pub dry_run: bool,
+
+    #[command(flatten)]
+    pub output: JsonArgs,
+}
+
+/// parse `7`, `7d` or `2w` into a number of days
+fn parse_days(value: &str) -> Result<u32, String> {
+    let (number, multiplier) = match value.strip_suffix('w') {
+        Some(weeks) => (weeks, 7),
+        None => (value.strip_suffix('d').unwrap_or(value), 1),
+    };
+
+    let days = match number.parse::<u32>() {
+        Ok(val) if val > 0 => val.checked_mul(multiplier),
+        _ => {
+            return Err(format!(
+                "invalid duration {}, expected days like 7d or weeks like 2w",
+                value
+            ))
+        }
+    };
+
+    match days {
+        Some(val) if val <= MAX_SNOOZE_DAYS => Ok(val),
+        _ => Err(format!(
+            "duration {} is too long, snooze for at most {} days",
+            value, MAX_SNOOZE_DAYS
+        )),
+    }
+}
+
+#[derive(Args)]
+pub struct DeleteArgs {
+    #[arg(add = ArgValueCompleter::new(complete_links))]
+    pub link: String,
+
+    #[command(flatten)]
+    pub confirm: ConfirmArgs,
+
+    #[command(flatten)]
+    pub output: JsonArgs,
+}
+
+#[derive(Args)]
+pub struct ServeArgs {
+    /// port to listen on, only localhost can connect
+    #[arg(long, default_value_t = 8080)]
+    pub port: u16,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ExportFormat {
+    /// one link per line, the format read by --file
+    Text,
+    /// an iCalendar file with an event on the review date of every completed link
+    Ics,
+    /// an Anki text file with a card per link, imported through File > Import
+    AnkiTsv,
+}
+
+#[derive(Args)]
+pub struct ExportArgs {
+    /// write to this file instead of stdout
+    #[arg(long, short, value_name = "FILE_PATH")]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = ExportFormat::Text)]
+    pub format: ExportFormat,
+}
+
+#[derive(Args)]
+pub struct SearchArgs {
+    /// search terms, narrow down with tag:, status:, platform:, collection:, url:, title: and notes:
+    #[arg(required = true)]
+    pub query: Vec<String>,
+
+    /// maximum number of links to show
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+}
+
+#[derive(Args)]
+pub struct ResetArgs {
+    /// reset completed links
+    #[arg(long)]
+    pub completed: bool,
+
+    /// reset skipped links
+    #[arg(long)]
+    pub skipped: bool,
+
+    /// only reset links with this tag
+    #[arg(long, add = ArgValueCompleter::new(complete_tags))]
+    pub tag: Option<String>,
+
+    /// only reset links from this platform, e.g. leetcode or leetcode.com
+    #[arg(long)]
+    pub platform: Option<String>,
+
+    /// only reset links that belong to this collection
+    #[arg(long, add = ArgValueCompleter::new(complete_collections))]
+    pub collection: Option<String>,
+
+    /// only reset links completed before this date (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE")]
+    pub before: Option<String>,
+
+    /// only reset links solved fewer than N times
+    #[arg(long, value_name = "N")]
+    pub solved_below: Option<i32>,
+
+    /// only reset these links, can be given multiple times
+    #[arg(long = "link", value_name = "LINK", add = ArgValueCompleter::new(complete_links))]
+    pub links: Vec<String>,
+
+    #[command(flatten)]
+    pub confirm: ConfirmArgs,
+}
+
+/// shared by every subcommand that changes the db
+#[derive(Args)]
+pub struct ConfirmArgs {
+    /// skip the confirmation prompt, required when stdin is not a terminal
+    #[arg(long, short)]
+    pub yes: bool,
+
+    /// show what would change without touching the db
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_days_and_weeks() {
+        assert_eq!(parse_days("7"), Ok(7));
+        assert_eq!(parse_days("7d"), Ok(7));
+        assert_eq!(parse_days("2w"), Ok(14));
+        assert_eq!(parse_days("3650d"), Ok(MAX_SNOOZE_DAYS));
+    }
+
+    #[test]
+    fn rejects_empty_and_overlong_durations() {
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("2x").is_err());
+        assert!(parse_days("3651d").is_err());
+        assert!(parse_days("99999999d").is_err());
+        assert!(parse_days("999999999w").is_err());
+    }
+}
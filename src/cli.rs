@@ -1,8 +1,17 @@
-use crate::print::{pretty_print, pretty_status};
-use crate::utility::show_red;
-use crate::CustomErrors;
-use crate::{database::Db, utility::show_green};
-use inquire::{required, validator::Validation, Select, Text};
+use crate::clipboard::copy_to_clipboard;
+use crate::print::{pretty_collection_status, pretty_print, pretty_status};
+use crate::prompt::{ask, confirm};
+use crate::timer::{format_duration, run_timer, TimerOutcome};
+use crate::utility::{is_valid_date, show_error, show_green, show_red};
+use abhyas::config::Config;
+use abhyas::database::{link_platform, BulkAction, Db, ResetFilter, MAX_SNOOZE_DAYS};
+use abhyas::link::{LinkFilter, LinkSort, LinkState};
+use abhyas::search::{SearchQuery, SEARCH_HELP};
+use abhyas::CustomErrors;
+use inquire::{required, validator::Validation, CustomType, MultiSelect, Select, Text};
+
+/// maximum number of ranked results shown by the interactive search
+const SEARCH_RESULTS_LIMIT: usize = 100;
 
 enum MainMenuOptions {
     Status,
@@ -10,6 +19,7 @@ enum MainMenuOptions {
     AddLink,
     DeleteLink,
     SearchLink,
+    BulkActions,
     Other,
     Exit,
 }
@@ -20,9 +30,25 @@ enum DeleteOptions {
     Exit,
 }
 
+enum BulkOptions {
+    Delete,
+    Skip,
+    MarkAsComplete,
+    Reset,
+    Tag,
+    MoveToCollection,
+    MainMenu,
+    Exit,
+}
+
 enum GetLinkOptions {
+    StartTimer,
     MarkAsComplete,
+    CouldNotSolve,
     Skip,
+    Snooze,
+    CopyLink,
+    EditDetails,
     MainMenu,
     Exit,
 }
@@ -31,38 +57,33 @@ enum OtherOptions {
     ShowAllLinks,
     ShowCompletedLinks,
     ShowSkippedLinks,
+    ShowFailedLinks,
     SkippedToIncomplete,
     CompletedToIncomplete,
+    SelectiveReset,
     MainMenu,
     Exit,
 }
 
-pub fn show_options(db: &Db) -> Result<(), CustomErrors> {
+pub fn show_options(db: &Db, config: &Config) -> Result<(), CustomErrors> {
     let options = vec![
         "Check Status",
         "Get Link",
         "Add Link",
         "Delete Link",
         "Search Link",
+        "Bulk Actions",
         "Other",
         "Exit",
     ];
 
-    let user_option = match Select::new("select your option", options).prompt() {
-        Ok(val) => val,
-        Err(e) => match e {
-            inquire::InquireError::OperationCanceled => {
-                return Err(CustomErrors::OperationCanceled)
-            }
-            inquire::InquireError::OperationInterrupted => {
-                return Err(CustomErrors::OperationInterrupted)
-            }
-            _ => {
-                return Err(CustomErrors::Others(
-                    "Error: Unable to show the select menu".to_owned(),
-                ))
-            }
-        },
+    // escape on the main menu has no level to go back to, so it quits
+    let user_option = match ask(
+        Select::new("select your option", options).prompt_skippable(),
+        "Unable to show the select menu",
+    )? {
+        Some(val) => val,
+        None => return Err(CustomErrors::Exit),
     };
 
     let selected_item = match user_option {
@@ -71,6 +92,7 @@ pub fn show_options(db: &Db) -> Result<(), CustomErrors> {
         "Add Link" => MainMenuOptions::AddLink,
         "Delete Link" => MainMenuOptions::DeleteLink,
         "Search Link" => MainMenuOptions::SearchLink,
+        "Bulk Actions" => MainMenuOptions::BulkActions,
         "Other" => MainMenuOptions::Other,
         "Exit" => MainMenuOptions::Exit,
         _ => unreachable!(),
@@ -78,10 +100,11 @@ pub fn show_options(db: &Db) -> Result<(), CustomErrors> {
 
     match selected_item {
         MainMenuOptions::Status => get_status(db)?,
-        MainMenuOptions::GetLink => get_link_options(db)?,
+        MainMenuOptions::GetLink => get_link_options(db, config)?,
         MainMenuOptions::AddLink => add_link_options(db)?,
         MainMenuOptions::DeleteLink => delete_link_options(db)?,
-        MainMenuOptions::SearchLink => search_link_options(db)?,
+        MainMenuOptions::SearchLink => search_link_options(db, config)?,
+        MainMenuOptions::BulkActions => bulk_action_options(db)?,
         MainMenuOptions::Other => show_other_options(db)?,
         MainMenuOptions::Exit => return Err(CustomErrors::Exit),
     }
@@ -90,26 +113,53 @@ pub fn show_options(db: &Db) -> Result<(), CustomErrors> {
 }
 
 fn get_status(db: &Db) -> Result<(), CustomErrors> {
-    match db.get_status() {
-        Ok(val) => {
-            match val {
-                Some((total_links, completed_links, skipped_links)) => {
-                    pretty_status(total_links, completed_links, skipped_links)
-                }
-                None => pretty_status(0, 0, 0),
-            };
-        }
+    match db.get_status_summary() {
+        Ok(status) => pretty_status(
+            status.total,
+            status.completed,
+            status.skipped,
+            status.failed,
+        ),
         Err(e) => return Err(e),
     };
+
+    let collection_status = db.get_collection_status()?;
+    if !collection_status.is_empty() {
+        pretty_collection_status(&collection_status);
+    }
+
     Ok(())
 }
 
-fn get_link_options(db: &Db) -> Result<(), CustomErrors> {
-    let link = match db.get_single_link() {
+fn get_link_options(db: &Db, config: &Config) -> Result<(), CustomErrors> {
+    let collections = db.get_collections()?;
+
+    let collection = if collections.is_empty() {
+        None
+    } else {
+        let mut scopes = vec!["All Links".to_owned()];
+        scopes.extend(collections);
+
+        match ask(
+            Select::new("get link from", scopes).prompt_skippable(),
+            "Something went wrong while showing collections",
+        )? {
+            Some(val) if val == "All Links" => None,
+            Some(val) => Some(val),
+            None => return Ok(()),
+        }
+    };
+
+    let next_link = match &collection {
+        Some(name) => db.get_next_collection_link(name),
+        None => db.get_single_link(),
+    };
+
+    let link = match next_link {
         Ok(val) => match val {
-            Some((link, solved_count)) => {
-                pretty_print(&[(link.clone(), solved_count)]);
-                link
+            Some(link) => {
+                pretty_print(std::slice::from_ref(&link));
+                link.link
             }
             None => {
                 show_red("No unsolved links, add new links or reset the link status");
@@ -119,7 +169,7 @@ fn get_link_options(db: &Db) -> Result<(), CustomErrors> {
         Err(e) => return Err(e),
     };
 
-    single_link_options(db, &link)?;
+    single_link_options(db, config, &link)?;
 
     Ok(())
 }
@@ -136,26 +186,16 @@ fn add_link_options(db: &Db) -> Result<(), CustomErrors> {
         }
     };
 
-    let link = match Text::new("Enter the link:")
-        .with_validator(required!())
-        .with_validator(validator)
-        .with_help_message("enter the link you want to save and hit enter")
-        .prompt()
-    {
-        Ok(val) => val,
-        Err(e) => match e {
-            inquire::InquireError::OperationCanceled => {
-                return Err(CustomErrors::OperationCanceled)
-            }
-            inquire::InquireError::OperationInterrupted => {
-                return Err(CustomErrors::OperationInterrupted)
-            }
-            _ => {
-                return Err(CustomErrors::Others(
-                    "Error: Something went wrong while taking link input".to_owned(),
-                ))
-            }
-        },
+    let link = match ask(
+        Text::new("Enter the link:")
+            .with_validator(required!())
+            .with_validator(validator)
+            .with_help_message("enter the link you want to save and hit enter")
+            .prompt_skippable(),
+        "Something went wrong while taking link input",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
     };
 
     match db.add_link(link.clone()) {
@@ -169,24 +209,22 @@ fn add_link_options(db: &Db) -> Result<(), CustomErrors> {
 fn delete_link_options(db: &Db) -> Result<(), CustomErrors> {
     let links = db.get_links()?;
 
-    let link = match Select::new("select link to delete", links).prompt() {
-        Ok(val) => val,
-        Err(_) => {
-            return Err(CustomErrors::Others(
-                "Error: Something went wrong while deleting links".to_owned(),
-            ))
-        }
+    let link = match ask(
+        Select::new("select link to delete", links).prompt_skippable(),
+        "Something went wrong while deleting links",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
     };
 
     let options = vec!["Delete Link", "Main Menu", "Exit"];
 
-    let choice = match Select::new("select your option", options).prompt() {
-        Ok(val) => val,
-        Err(_) => {
-            return Err(CustomErrors::Others(
-                "Error: Something went wrong while showing delete options".to_owned(),
-            ))
-        }
+    let choice = match ask(
+        Select::new("select your option", options).prompt_skippable(),
+        "Something went wrong while showing delete options",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
     };
 
     let selected_option = match choice {
@@ -198,6 +236,15 @@ fn delete_link_options(db: &Db) -> Result<(), CustomErrors> {
 
     match selected_option {
         DeleteOptions::DeleteLink => {
+            let history_count = db.get_link_history(&link)?.len();
+            if !confirm(&format!(
+                "Delete {} along with its {} history entries, tags and collection memberships?",
+                link, history_count
+            ))? {
+                show_red("Delete cancelled, no links were changed");
+                return Ok(());
+            }
+
             match db.delete_link(link) {
                 Ok(_) => show_green("Successfully deleted the link"),
                 Err(e) => return Err(e),
@@ -210,43 +257,122 @@ fn delete_link_options(db: &Db) -> Result<(), CustomErrors> {
     Ok(())
 }
 
-fn single_link_options(db: &Db, link: &str) -> Result<(), CustomErrors> {
+fn single_link_options(db: &Db, config: &Config, link: &str) -> Result<(), CustomErrors> {
     let options = vec![
+        "Start Timer",
         "Mark As Complete?",
+        "Couldn't Solve",
         "Skip And Go To Main Menu?",
+        "Snooze For A Few Days?",
+        "Copy Link",
+        "Edit Title And Notes",
         "Main Menu",
         "Exit",
     ];
-    let choice = match Select::new("Select your option", options).prompt() {
-        Ok(val) => val,
-        Err(_) => {
-            return Err(CustomErrors::Others(
-                "Error: Something went wrong while showing options".to_owned(),
-            ))
-        }
+    let choice = match ask(
+        Select::new("Select your option", options).prompt_skippable(),
+        "Something went wrong while showing options",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
     };
 
     let selected_option = match choice {
+        "Start Timer" => GetLinkOptions::StartTimer,
         "Mark As Complete?" => GetLinkOptions::MarkAsComplete,
+        "Couldn't Solve" => GetLinkOptions::CouldNotSolve,
         "Skip And Go To Main Menu?" => GetLinkOptions::Skip,
+        "Snooze For A Few Days?" => GetLinkOptions::Snooze,
+        "Copy Link" => GetLinkOptions::CopyLink,
+        "Edit Title And Notes" => GetLinkOptions::EditDetails,
         "Main Menu" => GetLinkOptions::MainMenu,
         "Exit" => GetLinkOptions::Exit,
         _ => unreachable!(),
     };
 
     match selected_option {
+        GetLinkOptions::StartTimer => match run_timer()? {
+            TimerOutcome::Completed(secs) => {
+                db.mark_as_complete_timed(link, secs)?;
+                show_green(format!("Solved in {}", format_duration(secs)).as_str());
+
+                if let Some(times) = db
+                    .get_link_times()?
+                    .into_iter()
+                    .find(|times| times.name == link)
+                {
+                    show_green(
+                        format!(
+                            "Best {} and average {} over {} timed attempts",
+                            format_duration(times.best_secs),
+                            format_duration(times.average_secs),
+                            times.attempts
+                        )
+                        .as_str(),
+                    );
+                }
+            }
+            TimerOutcome::Stopped => {
+                show_red("Timer stopped, no time was recorded");
+                return single_link_options(db, config, link);
+            }
+        },
         GetLinkOptions::MarkAsComplete => {
             match db.mark_as_complete(link) {
                 Ok(_) => show_green("Successfully marked the link as completed"),
                 Err(e) => return Err(e),
             };
         }
+        GetLinkOptions::CouldNotSolve => {
+            match db.mark_as_failed(link) {
+                Ok(_) => show_green("Recorded the failed attempt, the link will come back first"),
+                Err(e) => return Err(e),
+            };
+        }
         GetLinkOptions::Skip => {
             match db.skip_link(link) {
                 Ok(_) => show_green("Successfully skipped the link"),
                 Err(e) => return Err(e),
             };
         }
+        GetLinkOptions::Snooze => {
+            let days = match ask(
+                CustomType::<u32>::new("Snooze for how many days?")
+                    .with_default(7)
+                    .with_error_message("Please enter a valid number")
+                    .with_validator(|days: &u32| {
+                        if *days == 0 {
+                            Ok(Validation::Invalid("Snooze for at least one day".into()))
+                        } else if *days > MAX_SNOOZE_DAYS {
+                            Ok(Validation::Invalid(
+                                format!("Snooze for at most {} days", MAX_SNOOZE_DAYS).into(),
+                            ))
+                        } else {
+                            Ok(Validation::Valid)
+                        }
+                    })
+                    .prompt_skippable(),
+                "Something went wrong while taking snooze input",
+            )? {
+                Some(val) => val,
+                None => return single_link_options(db, config, link),
+            };
+
+            match db.snooze_link(link, days) {
+                Ok(_) => {
+                    show_green(format!("Successfully snoozed the link for {} days", days).as_str())
+                }
+                Err(e) => return Err(e),
+            };
+        }
+        GetLinkOptions::CopyLink => {
+            match copy_to_clipboard(config, link) {
+                Ok(_) => show_green("Copied the link to the clipboard"),
+                Err(e) => show_error(&format!("Error: {}", e)),
+            };
+            return single_link_options(db, config, link);
+        }
+        GetLinkOptions::EditDetails => edit_link_details(db, link)?,
         GetLinkOptions::MainMenu => (),
         GetLinkOptions::Exit => return Err(CustomErrors::Exit),
     };
@@ -254,84 +380,274 @@ fn single_link_options(db: &Db, link: &str) -> Result<(), CustomErrors> {
     Ok(())
 }
 
-fn search_link_options(db: &Db) -> Result<(), CustomErrors> {
-    let links = db.get_links()?;
+fn edit_link_details(db: &Db, link: &str) -> Result<(), CustomErrors> {
+    let details = match db.get_link(link)? {
+        Some(val) => val,
+        None => {
+            show_red("The link does not exist anymore");
+            return Ok(());
+        }
+    };
+
+    let title = match ask(
+        Text::new("Enter the title:")
+            .with_initial_value(&details.title)
+            .prompt_skippable(),
+        "Something went wrong while taking title input",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
+    };
+
+    let notes = match ask(
+        Text::new("Enter the notes:")
+            .with_initial_value(&details.notes)
+            .prompt_skippable(),
+        "Something went wrong while taking notes input",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
+    };
+
+    match db.update_link_details(link, title.trim(), notes.trim()) {
+        Ok(_) => show_green("Successfully updated the link details"),
+        Err(e) => return Err(e),
+    };
+
+    Ok(())
+}
 
-    let link = match Select::new("select link or type keywords", links).prompt() {
+fn search_link_options(db: &Db, config: &Config) -> Result<(), CustomErrors> {
+    let input = match ask(
+        Text::new("search links:")
+            .with_help_message(SEARCH_HELP)
+            .prompt_skippable(),
+        "Something went wrong while taking search input",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
+    };
+
+    let query = match SearchQuery::parse(&input) {
         Ok(val) => val,
-        Err(_) => {
-            return Err(CustomErrors::Others(
-                "Error: Something went wrong while searching links".to_owned(),
-            ))
+        Err(CustomErrors::InvalidInput(msg)) => {
+            show_red(&msg);
+            return Ok(());
         }
+        Err(e) => return Err(e),
     };
 
-    match db.get_searched_link_count(&link) {
-        Ok(val) => match val {
-            Some(solved_count) => pretty_print(&[(link.clone(), solved_count)]),
-            None => pretty_print(&[(link.clone(), 0)]),
-        },
-        Err(e) => return Err(e),
+    let results = db.search_links(&query, SEARCH_RESULTS_LIMIT)?;
+    if results.is_empty() {
+        show_red("No links matched the search");
+        return Ok(());
+    }
+
+    let links: Vec<String> = results.iter().map(|link| link.link.clone()).collect();
+    let link = match ask(
+        Select::new("select link", links).prompt_skippable(),
+        "Something went wrong while searching links",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
     };
 
-    single_link_options(db, &link)?;
+    if let Some(details) = db.get_link(&link)? {
+        pretty_print(&[details]);
+    }
+
+    single_link_options(db, config, &link)?;
 
     Ok(())
 }
 
+fn bulk_action_options(db: &Db) -> Result<(), CustomErrors> {
+    let links = db.get_links()?;
+    if links.is_empty() {
+        show_red("No Links present in the database :(");
+        return Ok(());
+    }
+
+    let selected_links = match ask(
+        MultiSelect::new("select links", links)
+            .with_help_message("space to select, type to filter, enter to confirm")
+            .prompt_skippable(),
+        "Something went wrong while selecting links",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
+    };
+
+    if selected_links.is_empty() {
+        show_red("No links selected");
+        return Ok(());
+    }
+
+    let options = vec![
+        "Delete Links",
+        "Skip Links",
+        "Mark Links As Complete",
+        "Reset Links To Incomplete",
+        "Tag Links",
+        "Move Links To Collection",
+        "Main Menu",
+        "Exit",
+    ];
+
+    let choice = match ask(
+        Select::new("Select your option", options).prompt_skippable(),
+        "Something went wrong while showing bulk options",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
+    };
+
+    let selected_option = match choice {
+        "Delete Links" => BulkOptions::Delete,
+        "Skip Links" => BulkOptions::Skip,
+        "Mark Links As Complete" => BulkOptions::MarkAsComplete,
+        "Reset Links To Incomplete" => BulkOptions::Reset,
+        "Tag Links" => BulkOptions::Tag,
+        "Move Links To Collection" => BulkOptions::MoveToCollection,
+        "Main Menu" => BulkOptions::MainMenu,
+        "Exit" => BulkOptions::Exit,
+        _ => unreachable!(),
+    };
+
+    let (action, summary) = match selected_option {
+        BulkOptions::Delete => (BulkAction::Delete, "Delete".to_owned()),
+        BulkOptions::Skip => (BulkAction::Skip, "Skip".to_owned()),
+        BulkOptions::MarkAsComplete => (BulkAction::MarkAsComplete, "Mark as complete".to_owned()),
+        BulkOptions::Reset => (BulkAction::Reset, "Reset to incomplete".to_owned()),
+        BulkOptions::Tag => {
+            let tag = match ask(
+                Text::new("Enter the tag:")
+                    .with_validator(required!())
+                    .prompt_skippable(),
+                "Something went wrong while taking tag input",
+            )? {
+                Some(val) => val.trim().to_owned(),
+                None => return Ok(()),
+            };
+            let summary = format!("Tag with \"{}\"", tag);
+            (BulkAction::Tag(tag), summary)
+        }
+        BulkOptions::MoveToCollection => {
+            let collection = match select_or_create_collection(db)? {
+                Some(val) => val,
+                None => return Ok(()),
+            };
+            let summary = format!("Move to the collection \"{}\"", collection);
+            (BulkAction::MoveToCollection(collection), summary)
+        }
+        BulkOptions::MainMenu => return Ok(()),
+        BulkOptions::Exit => return Err(CustomErrors::Exit),
+    };
+
+    println!("{} the following {} links:", summary, selected_links.len());
+    for link in &selected_links {
+        println!("  {}", link);
+    }
+
+    if !confirm(&format!(
+        "Apply to all {} selected links?",
+        selected_links.len()
+    ))? {
+        show_red("Bulk action cancelled, no links were changed");
+        return Ok(());
+    }
+
+    match db.apply_bulk_action(&action, &selected_links) {
+        Ok(count) => show_green(format!("Successfully updated {} links", count).as_str()),
+        Err(e) => return Err(e),
+    };
+
+    Ok(())
+}
+
+fn select_or_create_collection(db: &Db) -> Result<Option<String>, CustomErrors> {
+    let mut options = vec!["New Collection".to_owned()];
+    options.extend(db.get_collections()?);
+
+    let choice = match ask(
+        Select::new("select collection", options).prompt_skippable(),
+        "Something went wrong while showing collections",
+    )? {
+        Some(val) => val,
+        None => return Ok(None),
+    };
+
+    if choice != "New Collection" {
+        return Ok(Some(choice));
+    }
+
+    let name = ask(
+        Text::new("Enter the collection name:")
+            .with_validator(required!())
+            .prompt_skippable(),
+        "Something went wrong while taking collection input",
+    )?;
+    Ok(name.map(|name| name.trim().to_owned()))
+}
+
 fn show_other_options(db: &Db) -> Result<(), CustomErrors> {
     let options = vec![
         "Show All Links?",
         "Show Completed Links?",
         "Show Skipped Links?",
+        "Show Failed Links?",
         "Change All Skipped Links to Incomplete?",
         "Change All Completed Links to Incomplete?",
+        "Reset Selected Links to Incomplete?",
         "Main Menu",
         "Exit",
     ];
-    let choice = match Select::new("Select your option", options).prompt() {
-        Ok(val) => val,
-        Err(_) => {
-            return Err(CustomErrors::Others(
-                "Error: Something went wrong while showing options".to_owned(),
-            ))
-        }
+    let choice = match ask(
+        Select::new("Select your option", options).prompt_skippable(),
+        "Something went wrong while showing options",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
     };
 
     let selected_option = match choice {
         "Show All Links?" => OtherOptions::ShowAllLinks,
         "Show Completed Links?" => OtherOptions::ShowCompletedLinks,
         "Show Skipped Links?" => OtherOptions::ShowSkippedLinks,
+        "Show Failed Links?" => OtherOptions::ShowFailedLinks,
         "Change All Skipped Links to Incomplete?" => OtherOptions::SkippedToIncomplete,
         "Change All Completed Links to Incomplete?" => OtherOptions::CompletedToIncomplete,
+        "Reset Selected Links to Incomplete?" => OtherOptions::SelectiveReset,
         "Main Menu" => OtherOptions::MainMenu,
         "Exit" => OtherOptions::Exit,
         _ => unreachable!(),
     };
 
     match selected_option {
-        OtherOptions::ShowAllLinks => match db.get_all_links() {
-            Ok(val) => match val {
-                Some(all_links) => pretty_print(&all_links),
-                None => show_red("No Links present in the database :("),
-            },
-            Err(e) => return Err(e),
-        },
-        OtherOptions::ShowCompletedLinks => match db.get_completed_links() {
-            Ok(val) => match val {
-                Some(completed_links) => pretty_print(&completed_links),
-                None => show_red("No Completed Links :("),
-            },
-            Err(e) => return Err(e),
-        },
-        OtherOptions::ShowSkippedLinks => match db.get_skipped_links() {
-            Ok(val) => match val {
-                Some(skipped_links) => pretty_print(&skipped_links),
-                None => show_red("No Skipped Links :)"),
-            },
-            Err(e) => return Err(e),
-        },
+        OtherOptions::ShowAllLinks => show_links(db, None, "No Links present in the database :(")?,
+        OtherOptions::ShowCompletedLinks => {
+            show_links(db, Some(LinkState::Completed), "No Completed Links :(")?
+        }
+        OtherOptions::ShowSkippedLinks => {
+            show_links(db, Some(LinkState::Skipped), "No Skipped Links :)")?
+        }
+        OtherOptions::ShowFailedLinks => {
+            show_links(db, Some(LinkState::Failed), "No Failed Links :)")?
+        }
         OtherOptions::SkippedToIncomplete => {
+            let skipped_links = db.get_status_summary()?.skipped;
+            if skipped_links == 0 {
+                show_red("No Skipped Links :)");
+                return Ok(());
+            }
+            if !confirm(&format!(
+                "Change all {} skipped links to incomplete?",
+                skipped_links
+            ))? {
+                show_red("Reset cancelled, no links were changed");
+                return Ok(());
+            }
+
             match db.skipped_to_incomplete() {
                 Ok(count) => show_green(
                     format!("Changed {} Skipped Links To Incomplete Links", count).as_str(),
@@ -340,6 +656,19 @@ fn show_other_options(db: &Db) -> Result<(), CustomErrors> {
             };
         }
         OtherOptions::CompletedToIncomplete => {
+            let completed_links = db.get_status_summary()?.completed;
+            if completed_links == 0 {
+                show_red("No Completed Links :(");
+                return Ok(());
+            }
+            if !confirm(&format!(
+                "Change all {} completed links to incomplete?",
+                completed_links
+            ))? {
+                show_red("Reset cancelled, no links were changed");
+                return Ok(());
+            }
+
             match db.completed_to_incomplete() {
                 Ok(count) => show_green(
                     format!("Changed {} Completed Links To Incomplete Links", count).as_str(),
@@ -347,9 +676,169 @@ fn show_other_options(db: &Db) -> Result<(), CustomErrors> {
                 Err(e) => return Err(e),
             };
         }
+        OtherOptions::SelectiveReset => selective_reset_options(db)?,
         OtherOptions::MainMenu => (),
         OtherOptions::Exit => return Err(CustomErrors::Exit),
     }
 
     Ok(())
 }
+
+fn show_links(db: &Db, state: Option<LinkState>, empty_msg: &str) -> Result<(), CustomErrors> {
+    let filter = LinkFilter {
+        state,
+        ..Default::default()
+    };
+
+    let links = db.query_links(&filter, LinkSort::Added, None, 0)?;
+    if links.is_empty() {
+        show_red(empty_msg);
+    } else {
+        pretty_print(&links);
+    }
+
+    Ok(())
+}
+
+fn selective_reset_options(db: &Db) -> Result<(), CustomErrors> {
+    let state_options = vec![
+        "Completed Links",
+        "Skipped Links",
+        "Completed And Skipped Links",
+    ];
+    let state = match ask(
+        Select::new("which links should be reset?", state_options).prompt_skippable(),
+        "Something went wrong while showing reset options",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
+    };
+
+    let mut filter = ResetFilter {
+        completed: state != "Skipped Links",
+        skipped: state != "Completed Links",
+        ..Default::default()
+    };
+
+    let filter_options = vec![
+        "Tag",
+        "Platform",
+        "Collection",
+        "Completed Before Date",
+        "Solved Count Below",
+        "Pick Links",
+    ];
+    let selected_filters = match ask(
+        MultiSelect::new("narrow down by", filter_options)
+            .with_help_message("space to select, enter to confirm, select nothing to reset all")
+            .prompt_skippable(),
+        "Something went wrong while showing reset filters",
+    )? {
+        Some(val) => val,
+        None => return Ok(()),
+    };
+
+    for selected_filter in selected_filters {
+        let result = match selected_filter {
+            "Tag" => {
+                let tags = db.get_tags()?;
+                if tags.is_empty() {
+                    show_red("No tags present in the database, ignoring the tag filter");
+                    continue;
+                }
+                Select::new("select tag", tags)
+                    .prompt_skippable()
+                    .map(|tag| tag.map(|tag| filter.tag = Some(tag)))
+            }
+            "Platform" => {
+                let mut platforms: Vec<String> = db
+                    .get_links()?
+                    .iter()
+                    .filter_map(|link| link_platform(link))
+                    .collect();
+                platforms.sort();
+                platforms.dedup();
+                if platforms.is_empty() {
+                    continue;
+                }
+                Select::new("select platform", platforms)
+                    .prompt_skippable()
+                    .map(|platform| platform.map(|platform| filter.platform = Some(platform)))
+            }
+            "Collection" => {
+                let collections = db.get_collections()?;
+                if collections.is_empty() {
+                    show_red(
+                        "No collections present in the database, ignoring the collection filter",
+                    );
+                    continue;
+                }
+                Select::new("select collection", collections)
+                    .prompt_skippable()
+                    .map(|collection| {
+                        collection.map(|collection| filter.collection = Some(collection))
+                    })
+            }
+            "Completed Before Date" => Text::new("Enter the date (YYYY-MM-DD):")
+                .with_validator(|input: &str| {
+                    if is_valid_date(input) {
+                        Ok(Validation::Valid)
+                    } else {
+                        Ok(Validation::Invalid(
+                            "Invalid date, use the YYYY-MM-DD format".into(),
+                        ))
+                    }
+                })
+                .prompt_skippable()
+                .map(|date| date.map(|date| filter.completed_before = Some(date))),
+            "Solved Count Below" => CustomType::<i32>::new("Enter the solved count:")
+                .with_error_message("Please enter a valid number")
+                .prompt_skippable()
+                .map(|count| count.map(|count| filter.solved_below = Some(count))),
+            "Pick Links" => {
+                let picked = MultiSelect::new("select links", db.get_links()?).prompt_skippable();
+                // an empty list would reset every link
+                if matches!(&picked, Ok(Some(links)) if links.is_empty()) {
+                    show_red("No links picked, reset cancelled");
+                    return Ok(());
+                }
+                picked.map(|links| links.map(|links| filter.links = links))
+            }
+            _ => unreachable!(),
+        };
+
+        if ask(
+            result,
+            "Something went wrong while taking reset filter input",
+        )?
+        .is_none()
+        {
+            return Ok(());
+        }
+    }
+
+    let links = db.get_links_to_reset(&filter)?;
+    if links.is_empty() {
+        show_red("No links matched the given filters");
+        return Ok(());
+    }
+
+    println!("The following {} links will be reset:", links.len());
+    for link in &links {
+        println!("  {}", link);
+    }
+
+    if !confirm(&format!("Reset these {} links?", links.len()))? {
+        show_red("Reset cancelled, no links were changed");
+        return Ok(());
+    }
+
+    match db.reset_links(&filter) {
+        Ok(links) => {
+            show_green(format!("Changed {} Links To Incomplete Links", links.len()).as_str())
+        }
+        Err(e) => return Err(e),
+    };
+
+    Ok(())
+}
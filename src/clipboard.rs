@@ -0,0 +1,156 @@
+use abhyas::config::Config;
+use abhyas::CustomErrors;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn split_command<'a>(
+    command: &'a [String],
+    name: &str,
+) -> Result<(&'a String, &'a [String]), CustomErrors> {
+    match command.split_first() {
+        Some(val) => Ok(val),
+        None => Err(CustomErrors::InvalidInput(format!(
+            "The {} of the config is empty",
+            name
+        ))),
+    }
+}
+
+/// put the text into the clipboard with the copy command of the config
+pub fn copy_to_clipboard(config: &Config, text: &str) -> Result<(), CustomErrors> {
+    let (program, args) = split_command(&config.copy_command, "copy_command")?;
+
+    // wl-copy and xclip keep running to serve the clipboard, so their output must not
+    // hold on to the terminal
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+    {
+        Ok(val) => val,
+        Err(e) => {
+            return Err(CustomErrors::Io(
+                format!("Couldn't run the copy command {}", program),
+                e,
+            ))
+        }
+    };
+
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Err(e) = stdin.write_all(text.as_bytes()) {
+            return Err(CustomErrors::Io(
+                format!("Couldn't write to the copy command {}", program),
+                e,
+            ));
+        }
+    }
+
+    match child.wait() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(CustomErrors::Others(format!(
+            "The copy command {} failed with {}",
+            program, status
+        ))),
+        Err(e) => Err(CustomErrors::Io(
+            format!("Couldn't wait for the copy command {}", program),
+            e,
+        )),
+    }
+}
+
+/// returns the trimmed clipboard text read with the paste command of the config
+pub fn paste_from_clipboard(config: &Config) -> Result<String, CustomErrors> {
+    let (program, args) = split_command(&config.paste_command, "paste_command")?;
+
+    let output = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+    {
+        Ok(val) => val,
+        Err(e) => {
+            return Err(CustomErrors::Io(
+                format!("Couldn't run the paste command {}", program),
+                e,
+            ))
+        }
+    };
+
+    if !output.status.success() {
+        return Err(CustomErrors::Others(format!(
+            "The paste command {} failed with {}",
+            program, output.status
+        )));
+    }
+
+    match String::from_utf8(output.stdout) {
+        Ok(text) => Ok(text.trim().to_owned()),
+        Err(_) => Err(CustomErrors::InvalidInput(
+            "The clipboard doesn't hold text".to_owned(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    fn stub(script: &str) -> Vec<String> {
+        vec!["sh".to_owned(), "-c".to_owned(), script.to_owned()]
+    }
+
+    #[test]
+    fn copies_and_pastes_through_the_commands() -> Result<(), CustomErrors> {
+        let clipboard = env::temp_dir().join(format!("abhyas-clipboard-{}", process::id()));
+        let config = Config {
+            copy_command: stub(&format!("cat > '{}'", clipboard.display())),
+            paste_command: stub(&format!("cat '{}'; echo", clipboard.display())),
+            ..Config::default()
+        };
+
+        copy_to_clipboard(&config, "https://leetcode.com/problems/two-sum")?;
+        let pasted = paste_from_clipboard(&config)?;
+        let _ = fs::remove_file(clipboard);
+
+        assert_eq!(pasted, "https://leetcode.com/problems/two-sum");
+
+        Ok(())
+    }
+
+    #[test]
+    fn trims_the_pasted_text() -> Result<(), CustomErrors> {
+        let config = Config {
+            paste_command: stub("printf '  https://a.com \\n'"),
+            ..Config::default()
+        };
+
+        assert_eq!(paste_from_clipboard(&config)?, "https://a.com");
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_failing_and_missing_commands() {
+        let failing = Config {
+            paste_command: stub("exit 1"),
+            ..Config::default()
+        };
+        let missing = Config {
+            copy_command: vec![],
+            ..Config::default()
+        };
+
+        assert!(matches!(
+            paste_from_clipboard(&failing),
+            Err(CustomErrors::Others(_))
+        ));
+        assert!(matches!(
+            copy_to_clipboard(&missing, "link"),
+            Err(CustomErrors::InvalidInput(_))
+        ));
+    }
+}
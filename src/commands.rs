@@ -0,0 +1,637 @@
+use crate::args::{
+    AddArgs, Cli, Command, CompletionsArgs, ConfirmArgs, DeleteArgs, ExportArgs, ExportFormat,
+    JsonArgs, LinkArgs, ListArgs, MergeArgs, NextArgs, ResetArgs, SearchArgs, SessionArgs,
+    SessionsArgs, SnoozeArgs, SyncCommand, WebhooksCommand,
+};
+use crate::clipboard::paste_from_clipboard;
+use crate::print::{
+    pretty_collection_status, pretty_print, pretty_session_links, pretty_sessions,
+    pretty_solve_times, pretty_status, print_json, JsonError, NO_PENDING_LINKS,
+};
+use crate::prompt::confirm;
+use crate::remind::remind;
+use crate::server::serve;
+use crate::timer::{format_duration, run_countdown, CountdownOutcome};
+use crate::tui::run_tui;
+use crate::utility::{is_valid_date, open_in_browser, show_error, show_green, show_red};
+use abhyas::config::Config;
+use abhyas::database::{Db, ResetFilter};
+use abhyas::export::{
+    export_anki_tsv, export_ics, export_links, write_anki_tsv_to_file, write_ics_to_file,
+    write_links_to_file,
+};
+use abhyas::link::{Link, LinkFilter, LinkState, SelectionStrategy};
+use abhyas::search::SearchQuery;
+use abhyas::session::{Session, SessionLink, SessionOutcome};
+use abhyas::sync::{export_to_dir, import_from_dir, SyncSummary};
+use abhyas::CustomErrors;
+use clap::CommandFactory;
+use clap_complete::env::Shells;
+use clap_complete::generate;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+
+/// run a single non-interactive subcommand
+pub fn run_command(db: &Db, config: &Config, command: Command) -> Result<(), CustomErrors> {
+    match command {
+        Command::Reset(args) => reset_command(db, args),
+        Command::Search(args) => search_command(db, args),
+        Command::Tui => run_tui(db),
+        Command::Export(args) => export_command(db, config, args),
+        Command::List(args) => list_command(db, args),
+        Command::Next(args) => next_command(db, args),
+        Command::Add(args) => add_command(db, config, args),
+        Command::Done(args) => done_command(db, args),
+        Command::Skip(args) => skip_command(db, args),
+        Command::Snooze(args) => snooze_command(db, args),
+        Command::Fail(args) => fail_command(db, args),
+        Command::Delete(args) => delete_command(db, args),
+        Command::Status(args) => status_command(db, args),
+        Command::Stats(args) => stats_command(db, args),
+        Command::Serve(args) => serve(db, args.port),
+        Command::Sync(command) => sync_command(db, command),
+        Command::Merge(args) => merge_command(db, args),
+        Command::Completions(args) => completions_command(&args),
+        Command::Session(args) => session_command(db, args),
+        Command::Sessions(args) => sessions_command(db, args),
+        Command::Remind(args) => remind(db, args),
+        Command::Webhooks(command) => webhooks_command(db, command),
+    }
+}
+
+/// returns the link with all its details, or `LinkNotFound` if the link does not exist
+pub fn existing_link(db: &Db, link: &str) -> Result<Link, CustomErrors> {
+    match db.get_link(link)? {
+        Some(val) => Ok(val),
+        None => Err(CustomErrors::LinkNotFound(link.to_owned())),
+    }
+}
+
+fn reset_command(db: &Db, args: ResetArgs) -> Result<(), CustomErrors> {
+    if let Some(date) = &args.before {
+        if !is_valid_date(date) {
+            return Err(CustomErrors::InvalidInput(format!(
+                "Invalid date {}, expected the YYYY-MM-DD format",
+                date
+            )));
+        }
+    }
+
+    let filter = ResetFilter {
+        completed: args.completed,
+        skipped: args.skipped,
+        tag: args.tag,
+        platform: args.platform,
+        completed_before: args.before,
+        solved_below: args.solved_below,
+        collection: args.collection,
+        links: args.links,
+    };
+
+    let links = db.get_links_to_reset(&filter)?;
+    if links.is_empty() {
+        show_red("No links matched the given filters");
+        return Ok(());
+    }
+
+    for link in &links {
+        println!("{}", link);
+    }
+
+    if args.confirm.dry_run {
+        show_green(format!("Would change {} Links To Incomplete Links", links.len()).as_str());
+        return Ok(());
+    }
+
+    if !confirm_changes(
+        &args.confirm,
+        &format!("Reset these {} links to incomplete?", links.len()),
+    )? {
+        show_red("Reset cancelled, no links were changed");
+        return Ok(());
+    }
+
+    let links = db.reset_links(&filter)?;
+    show_green(format!("Changed {} Links To Incomplete Links", links.len()).as_str());
+
+    Ok(())
+}
+
+/// ask before changing the db unless --yes was passed, scripts without a terminal must pass --yes
+fn confirm_changes(args: &ConfirmArgs, message: &str) -> Result<bool, CustomErrors> {
+    if args.yes {
+        return Ok(true);
+    }
+
+    if !io::stdin().is_terminal() {
+        return Err(CustomErrors::InvalidInput(
+            "Refusing to change the db without confirmation, pass --yes or --dry-run".to_owned(),
+        ));
+    }
+
+    confirm(message)
+}
+
+fn search_command(db: &Db, args: SearchArgs) -> Result<(), CustomErrors> {
+    let query = SearchQuery::parse(&args.query.join(" "))?;
+    let links = db.search_links(&query, args.limit)?;
+
+    if links.is_empty() {
+        show_red("No links matched the search");
+    } else {
+        pretty_print(&links);
+    }
+
+    Ok(())
+}
+
+fn export_command(db: &Db, config: &Config, args: ExportArgs) -> Result<(), CustomErrors> {
+    match (args.format, args.output) {
+        (ExportFormat::Text, Some(file)) => {
+            let count = write_links_to_file(db, &file)?;
+            show_green(format!("Exported {} Links To {}", count, file.display()).as_str());
+        }
+        (ExportFormat::Text, None) => print!("{}", export_links(db)?),
+        (ExportFormat::Ics, Some(file)) => {
+            let count = write_ics_to_file(db, config.review_after_days, &file)?;
+            show_green(format!("Exported {} Reviews To {}", count, file.display()).as_str());
+        }
+        (ExportFormat::AnkiTsv, Some(file)) => {
+            let count = write_anki_tsv_to_file(db, &file)?;
+            show_green(format!("Exported {} Cards To {}", count, file.display()).as_str());
+        }
+        (ExportFormat::AnkiTsv, None) => print!("{}", export_anki_tsv(db)?),
+        (ExportFormat::Ics, None) => print!("{}", export_ics(db, config.review_after_days)?),
+    };
+
+    Ok(())
+}
+
+fn list_command(db: &Db, args: ListArgs) -> Result<(), CustomErrors> {
+    let filter = LinkFilter {
+        state: args.state.map(LinkState::from),
+        tag: args.tag,
+        collection: args.collection,
+    };
+    let links = db.query_links(&filter, args.sort.into(), args.limit, args.offset)?;
+
+    if args.output.json {
+        print_json(&links)
+    } else {
+        if links.is_empty() {
+            show_red("No links matched the given filters");
+        } else {
+            pretty_print(&links);
+        }
+        Ok(())
+    }
+}
+
+fn next_command(db: &Db, args: NextArgs) -> Result<(), CustomErrors> {
+    let link = match &args.collection {
+        Some(collection) => db.get_next_collection_link(collection)?,
+        None => db.get_single_link()?,
+    };
+
+    // the same shapes as `GET /links/next`, which answers a 404 with the error
+    match (link, args.output.json) {
+        (Some(val), true) => print_json(&val)?,
+        (None, true) => print_json(&JsonError {
+            error: NO_PENDING_LINKS.to_owned(),
+        })?,
+        (Some(val), false) => pretty_print(std::slice::from_ref(&val)),
+        (None, false) => show_red(&format!("{} :)", NO_PENDING_LINKS)),
+    };
+
+    Ok(())
+}
+
+fn add_command(db: &Db, config: &Config, args: AddArgs) -> Result<(), CustomErrors> {
+    let link = match args.link {
+        Some(val) => val.trim().to_owned(),
+        None => {
+            let text = paste_from_clipboard(config)?;
+            if text.contains(char::is_whitespace) {
+                return Err(CustomErrors::InvalidInput(
+                    "The clipboard holds more than a single link".to_owned(),
+                ));
+            }
+            text
+        }
+    };
+    if link.is_empty() {
+        return Err(CustomErrors::InvalidInput(
+            "The link can't be empty".to_owned(),
+        ));
+    }
+
+    if args.dry_run {
+        if db.get_link(&link)?.is_some() {
+            return Err(CustomErrors::DuplicateLinkValue);
+        }
+        show_green(format!("Would add the link {}", link).as_str());
+        return Ok(());
+    }
+
+    db.add_link(link.clone())?;
+    if args.output.json {
+        print_json(&existing_link(db, &link)?)
+    } else {
+        show_green(format!("Successfully added the link: {}", link).as_str());
+        Ok(())
+    }
+}
+
+fn done_command(db: &Db, args: LinkArgs) -> Result<(), CustomErrors> {
+    existing_link(db, &args.link)?;
+
+    if args.dry_run {
+        show_green(format!("Would mark the link {} as completed", args.link).as_str());
+        return Ok(());
+    }
+
+    db.mark_as_complete(&args.link)?;
+    if args.output.json {
+        print_json(&existing_link(db, &args.link)?)
+    } else {
+        show_green("Successfully marked the link as completed");
+        Ok(())
+    }
+}
+
+fn skip_command(db: &Db, args: LinkArgs) -> Result<(), CustomErrors> {
+    existing_link(db, &args.link)?;
+
+    if args.dry_run {
+        show_green(format!("Would skip the link {}", args.link).as_str());
+        return Ok(());
+    }
+
+    db.skip_link(&args.link)?;
+    if args.output.json {
+        print_json(&existing_link(db, &args.link)?)
+    } else {
+        show_green("Successfully skipped the link");
+        Ok(())
+    }
+}
+
+fn snooze_command(db: &Db, args: SnoozeArgs) -> Result<(), CustomErrors> {
+    existing_link(db, &args.link)?;
+
+    if args.dry_run {
+        show_green(format!("Would snooze the link {} for {} days", args.link, args.days).as_str());
+        return Ok(());
+    }
+
+    db.snooze_link(&args.link, args.days)?;
+    let link = existing_link(db, &args.link)?;
+    if args.output.json {
+        print_json(&link)
+    } else {
+        show_green(
+            format!(
+                "Successfully snoozed the link until {}",
+                link.skipped_until.unwrap_or_default()
+            )
+            .as_str(),
+        );
+        Ok(())
+    }
+}
+
+fn fail_command(db: &Db, args: LinkArgs) -> Result<(), CustomErrors> {
+    existing_link(db, &args.link)?;
+
+    if args.dry_run {
+        show_green(format!("Would record a failed attempt of the link {}", args.link).as_str());
+        return Ok(());
+    }
+
+    db.mark_as_failed(&args.link)?;
+    if args.output.json {
+        print_json(&existing_link(db, &args.link)?)
+    } else {
+        show_green("Successfully recorded the failed attempt");
+        Ok(())
+    }
+}
+
+fn delete_command(db: &Db, args: DeleteArgs) -> Result<(), CustomErrors> {
+    let link = existing_link(db, &args.link)?;
+    let history_count = db.get_link_history(&args.link)?.len();
+
+    if args.confirm.dry_run {
+        show_green(
+            format!(
+                "Would delete the link {} along with its {} history entries",
+                args.link, history_count
+            )
+            .as_str(),
+        );
+        return Ok(());
+    }
+
+    if !confirm_changes(
+        &args.confirm,
+        &format!(
+            "Delete {} along with its {} history entries, tags and collection memberships?",
+            args.link, history_count
+        ),
+    )? {
+        show_red("Delete cancelled, no links were changed");
+        return Ok(());
+    }
+
+    db.delete_link(args.link)?;
+    if args.output.json {
+        print_json(&link)
+    } else {
+        show_green("Successfully deleted the link");
+        Ok(())
+    }
+}
+
+fn status_command(db: &Db, args: JsonArgs) -> Result<(), CustomErrors> {
+    let status = db.get_status_summary()?;
+
+    if args.json {
+        print_json(&status)
+    } else {
+        pretty_status(
+            status.total,
+            status.completed,
+            status.skipped,
+            status.failed,
+        );
+        Ok(())
+    }
+}
+
+fn stats_command(db: &Db, args: JsonArgs) -> Result<(), CustomErrors> {
+    let stats = db.get_stats()?;
+
+    if args.json {
+        return print_json(&stats);
+    }
+
+    pretty_status(
+        stats.status.total,
+        stats.status.completed,
+        stats.status.skipped,
+        stats.status.failed,
+    );
+
+    if !stats.collections.is_empty() {
+        pretty_collection_status(&stats.collections);
+    }
+
+    if !stats.link_times.is_empty() {
+        show_green("Solve Times Per Link");
+        pretty_solve_times(&stats.link_times);
+        show_green("Solve Times Per Tag");
+        pretty_solve_times(&stats.tag_times);
+    }
+
+    let completed: i32 = stats.activity.iter().map(|day| day.completed).sum();
+    show_green(
+        format!(
+            "Completed {} Links On {} Days In The Last Year",
+            completed,
+            stats
+                .activity
+                .iter()
+                .filter(|day| day.completed > 0)
+                .count()
+        )
+        .as_str(),
+    );
+
+    Ok(())
+}
+
+fn sync_command(db: &Db, command: SyncCommand) -> Result<(), CustomErrors> {
+    match command {
+        SyncCommand::Export { dir } => {
+            let count = export_to_dir(db, &dir)?;
+            show_green(format!("Exported {} Links To {}", count, dir.display()).as_str());
+        }
+        SyncCommand::Import { dir, dry_run } => {
+            show_merge_summary(&import_from_dir(db, &dir, dry_run)?, dry_run)
+        }
+    };
+
+    Ok(())
+}
+
+fn webhooks_command(db: &Db, command: WebhooksCommand) -> Result<(), CustomErrors> {
+    match command {
+        WebhooksCommand::Flush => {
+            let summary = db.flush_webhooks()?;
+            for e in &summary.errors {
+                show_error(e);
+            }
+            show_green(
+                format!(
+                    "Delivered {} Events\nDropped {} Events\n{} Events Still Queued",
+                    summary.delivered, summary.dropped, summary.queued
+                )
+                .as_str(),
+            );
+        }
+    };
+
+    Ok(())
+}
+
+fn merge_command(db: &Db, args: MergeArgs) -> Result<(), CustomErrors> {
+    let summary = db.merge_database(&args.other, args.policy.into(), args.dry_run)?;
+    show_merge_summary(&summary, args.dry_run);
+
+    Ok(())
+}
+
+fn show_merge_summary(summary: &SyncSummary, dry_run: bool) {
+    let (added, updated) = if dry_run {
+        ("Would Add", "Would Update")
+    } else {
+        ("Added", "Updated")
+    };
+
+    show_green(
+        format!(
+            "{0} {1} New Links\n{2} {3} Existing Links\n{0} {4} History Entries\n{0} {5} Links To Collections",
+            added,
+            summary.added_links,
+            updated,
+            summary.updated_links,
+            summary.added_history,
+            summary.added_collection_links
+        )
+        .as_str(),
+    );
+}
+
+fn session_command(db: &Db, args: SessionArgs) -> Result<(), CustomErrors> {
+    if args.count == 0 || args.minutes == 0 {
+        return Err(CustomErrors::InvalidInput(
+            "A session needs at least one link and one minute".to_owned(),
+        ));
+    }
+
+    if !io::stdin().is_terminal() {
+        return Err(CustomErrors::InvalidInput(
+            "A session reads key presses and needs a terminal".to_owned(),
+        ));
+    }
+
+    let strategy = SelectionStrategy::from(args.strategy);
+    let links = db.select_links(strategy, args.tag.clone(), args.count)?;
+    if links.is_empty() {
+        show_red("No links matched the given strategy and tag");
+        return Ok(());
+    }
+    if links.len() < args.count {
+        show_red(
+            format!(
+                "Only {} links matched, starting a shorter session",
+                links.len()
+            )
+            .as_str(),
+        );
+    }
+
+    let id = db.start_session(args.minutes, strategy, args.tag.as_deref())?;
+    let deadline = Instant::now() + Duration::from_secs(args.minutes as u64 * 60);
+    let mut ended = false;
+
+    for (position, link) in links.iter().enumerate() {
+        // once the session is over the remaining links are recorded as skipped
+        let (outcome, duration_secs) = if ended {
+            (SessionOutcome::Skipped, 0)
+        } else {
+            show_green(format!("Link {} of {}: {}", position + 1, links.len(), link.link).as_str());
+            if !link.title.is_empty() {
+                println!("{}", link.title);
+            }
+            if args.open {
+                if let Err(e) = open_in_browser(&link.link) {
+                    show_error(&format!("Error: {}", e));
+                }
+            }
+
+            match run_countdown(deadline)? {
+                (CountdownOutcome::Solved, secs) => (SessionOutcome::Solved, secs),
+                (CountdownOutcome::Failed, secs) => (SessionOutcome::Failed, secs),
+                (CountdownOutcome::Skipped, secs) => (SessionOutcome::Skipped, secs),
+                (CountdownOutcome::Ended, secs) => {
+                    ended = true;
+                    (SessionOutcome::Skipped, secs)
+                }
+                (CountdownOutcome::TimeUp, secs) => {
+                    show_red("Time is up");
+                    ended = true;
+                    (SessionOutcome::Failed, secs)
+                }
+            }
+        };
+
+        match outcome {
+            SessionOutcome::Solved => db.mark_as_complete_timed(&link.link, duration_secs)?,
+            SessionOutcome::Failed => db.mark_as_failed(&link.link)?,
+            SessionOutcome::Skipped => (),
+        };
+        let entry = SessionLink {
+            link: link.link.clone(),
+            outcome,
+            duration_secs,
+        };
+        db.record_session_link(id, position, &entry)?;
+    }
+
+    match db.get_session(id)? {
+        Some(session) => show_session(&session),
+        None => show_red("The session couldn't be found after saving it"),
+    };
+
+    Ok(())
+}
+
+fn sessions_command(db: &Db, args: SessionsArgs) -> Result<(), CustomErrors> {
+    let id = match args.id {
+        Some(val) => val,
+        None => {
+            let sessions = db.get_sessions()?;
+            if args.output.json {
+                return print_json(&sessions);
+            }
+            if sessions.is_empty() {
+                show_red("No sessions yet, start one with abhyas session");
+            } else {
+                pretty_sessions(&sessions);
+            }
+            return Ok(());
+        }
+    };
+
+    let session = match db.get_session(id)? {
+        Some(val) => val,
+        None => {
+            return Err(CustomErrors::InvalidInput(format!(
+                "The session {} does not exist",
+                id
+            )))
+        }
+    };
+
+    if args.output.json {
+        print_json(&session)
+    } else {
+        show_session(&session);
+        Ok(())
+    }
+}
+
+fn show_session(session: &Session) {
+    pretty_session_links(&session.links);
+    show_green(
+        format!(
+            "Solved {} Of {} Links, Failed {}, In {} Of {} Minutes",
+            session.count(SessionOutcome::Solved),
+            session.links.len(),
+            session.count(SessionOutcome::Failed),
+            format_duration(session.total_secs()),
+            session.minutes
+        )
+        .as_str(),
+    );
+}
+
+/// print the completion script, it doesn't need the db
+pub fn completions_command(args: &CompletionsArgs) -> Result<(), CustomErrors> {
+    let mut stdout = io::stdout();
+
+    if !args.dynamic {
+        generate(args.shell, &mut Cli::command(), "abhyas", &mut stdout);
+        return Ok(());
+    }
+
+    // the dynamic script calls back into abhyas with COMPLETE set, see main
+    let shells = Shells::builtins();
+    let shell = match shells.completer(&args.shell.to_string()) {
+        Some(val) => val,
+        None => {
+            return Err(CustomErrors::InvalidInput(format!(
+                "Dynamic completions are not supported for {}",
+                args.shell
+            )))
+        }
+    };
+    match shell.write_registration("COMPLETE", "abhyas", "abhyas", "abhyas", &mut stdout) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(CustomErrors::Io(
+            "Couldn't write the completion script".to_owned(),
+            e,
+        )),
+    }
+}
@@ -0,0 +1,38 @@
+use abhyas::database::Db;
+use clap_complete::engine::CompletionCandidate;
+use std::ffi::OsStr;
+
+/// turn the values starting with what was typed so far into completion candidates
+fn candidates(values: Vec<String>, current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    values
+        .into_iter()
+        .filter(|value| value.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// complete the links saved in the db, nothing is offered when the db can't be read,
+/// the db is opened read-only so a TAB press never creates or upgrades it
+pub fn complete_links(current: &OsStr) -> Vec<CompletionCandidate> {
+    match Db::open_default_read_only().and_then(|db| db.get_links()) {
+        Ok(links) => candidates(links, current),
+        Err(_) => vec![],
+    }
+}
+
+/// complete the tags used in the db
+pub fn complete_tags(current: &OsStr) -> Vec<CompletionCandidate> {
+    match Db::open_default_read_only().and_then(|db| db.get_tags()) {
+        Ok(tags) => candidates(tags, current),
+        Err(_) => vec![],
+    }
+}
+
+/// complete the collection names of the db
+pub fn complete_collections(current: &OsStr) -> Vec<CompletionCandidate> {
+    match Db::open_default_read_only().and_then(|db| db.get_collections()) {
+        Ok(collections) => candidates(collections, current),
+        Err(_) => vec![],
+    }
+}
@@ -0,0 +1,109 @@
+use crate::hooks::Hooks;
+use crate::webhook::Webhook;
+use crate::CustomErrors;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// settings read from `config.toml` in the abhyas config directory, every
+/// field is optional and falls back to its default
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// links to complete every day
+    pub daily_goal: u32,
+    /// days after which a completed link is due for review
+    pub review_after_days: u32,
+    /// program and arguments that show a notification, the message is passed as the last argument
+    pub notify_command: Vec<String>,
+    /// program and arguments that put the text given on stdin into the clipboard
+    pub copy_command: Vec<String>,
+    /// program and arguments that print the clipboard on stdout
+    pub paste_command: Vec<String>,
+    /// commands run after links change
+    pub hooks: Hooks,
+    /// urls that get a json POST when links change, the `[[webhooks]]` entries
+    pub webhooks: Vec<Webhook>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            daily_goal: 1,
+            review_after_days: 30,
+            notify_command: vec!["notify-send".to_owned(), "abhyas".to_owned()],
+            copy_command: default_copy_command(),
+            paste_command: default_paste_command(),
+            hooks: Hooks::default(),
+            webhooks: vec![],
+        }
+    }
+}
+
+/// wl-copy on wayland, xclip on x11, pbcopy on macos
+fn default_copy_command() -> Vec<String> {
+    let command: &[&str] = if cfg!(target_os = "macos") {
+        &["pbcopy"]
+    } else if cfg!(target_os = "windows") {
+        &["clip"]
+    } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+        &["wl-copy"]
+    } else {
+        &["xclip", "-selection", "clipboard"]
+    };
+    command.iter().map(|arg| arg.to_string()).collect()
+}
+
+/// wl-paste on wayland, xclip on x11, pbpaste on macos
+fn default_paste_command() -> Vec<String> {
+    let command: &[&str] = if cfg!(target_os = "macos") {
+        &["pbpaste"]
+    } else if cfg!(target_os = "windows") {
+        &["powershell", "-NoProfile", "-Command", "Get-Clipboard"]
+    } else if env::var_os("WAYLAND_DISPLAY").is_some() {
+        &["wl-paste", "--no-newline"]
+    } else {
+        &["xclip", "-selection", "clipboard", "-out"]
+    };
+    command.iter().map(|arg| arg.to_string()).collect()
+}
+
+impl Config {
+    /// returns the path of `config.toml`, e.g. `~/.config/abhyas/config.toml` on linux
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("abhyas").join("config.toml"))
+    }
+
+    /// read the config from the default path, a missing file gives the defaults
+    pub fn load_default() -> Result<Self, CustomErrors> {
+        match Config::default_path() {
+            Some(path) => Config::load(&path),
+            None => Ok(Config::default()),
+        }
+    }
+
+    /// read the config from the given file, a missing file gives the defaults
+    pub fn load(path: &Path) -> Result<Self, CustomErrors> {
+        let data = match fs::read_to_string(path) {
+            Ok(val) => val,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => {
+                return Err(CustomErrors::Io(
+                    format!("Couldn't read the config file {}", path.display()),
+                    e,
+                ))
+            }
+        };
+
+        match toml::from_str(&data) {
+            Ok(val) => Ok(val),
+            Err(e) => Err(CustomErrors::InvalidInput(format!(
+                "Invalid config file {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+}
@@ -1,44 +1,461 @@
+use crate::hooks::{HookEvent, Hooks};
+use crate::link::{Link, LinkFilter, LinkSort, LinkState, SelectionStrategy};
+use crate::schema::{create_db_file, create_tables, db_file_path};
+use crate::search::{SearchQuery, StatusFilter};
+use crate::session::{Session, SessionLink, SessionOutcome};
+use crate::stats::{CollectionStatus, DayActivity, SolveTime, Stats, Status};
+use crate::sync::{CollectionRecord, HistoryEntry, LinkRecord, MergePolicy, SyncSummary};
+use crate::webhook::{DeliverySummary, WebhookEvent, Webhooks, STREAK_MILESTONES};
 use crate::CustomErrors;
+use rusqlite::backup::{Backup, StepResult};
 use rusqlite::ffi::{SQLITE_CONSTRAINT_PRIMARYKEY, SQLITE_CONSTRAINT_UNIQUE};
+use rusqlite::OpenFlags;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+/// actions that can be applied to many links at once
+pub enum BulkAction {
+    /// remove the links along with their tags, collections and history
+    Delete,
+    /// mark the links as skipped until a reset
+    Skip,
+    /// mark the links as skipped for this many days
+    Snooze(u32),
+    /// increase the solved count and mark the links as completed
+    MarkAsComplete,
+    /// increase the failed count and mark the links as failed
+    MarkAsFailed,
+    /// clear the completed, skipped and failed state
+    Reset,
+    /// add the tag to the links
+    Tag(String),
+    /// remove the links from their collections and append them to this one
+    MoveToCollection(String),
+}
+
+/// criteria used to pick the links that should go back to incomplete,
+/// when neither `completed` nor `skipped` is set both states are reset
+#[derive(Default)]
+pub struct ResetFilter {
+    /// reset completed links
+    pub completed: bool,
+    /// reset skipped links
+    pub skipped: bool,
+    /// only links with this tag
+    pub tag: Option<String>,
+    /// only links whose host matches, see `link_platform`
+    pub platform: Option<String>,
+    /// only links completed before this YYYY-MM-DD date, links solved before the
+    /// completion date was recorded count as completed before any date
+    pub completed_before: Option<String>,
+    /// only links solved fewer times than this
+    pub solved_below: Option<i32>,
+    /// only links that belong to this collection
+    pub collection: Option<String>,
+    /// only these links, ignored when empty
+    pub links: Vec<String>,
+}
+
+/// returns the host of the link without the `www.` prefix, e.g. `leetcode.com`
+pub fn link_platform(link: &str) -> Option<String> {
+    let without_scheme = match link.find("://") {
+        Some(index) => &link[index + 3..],
+        None => link,
+    };
+
+    let host = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches("www.")
+        .to_lowercase();
+
+    if host.is_empty() {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+/// true when the platform matches either the full host or its first label
+fn platform_matches(link: &str, platform: &str) -> bool {
+    let platform = platform.trim().to_lowercase();
+    match link_platform(link) {
+        Some(host) => host == platform || host.starts_with(&format!("{}.", platform)),
+        None => false,
+    }
+}
+
+/// columns read by `link_from_row`, tags are joined with the unit separator
+const LINK_COLUMNS: &str = "links.link, links.title, links.notes, links.solved_count,
+    links.is_solved, links.is_skipped, links.completed_at, links.failed_count, links.is_failed,
+    links.skipped_until,
+    COALESCE((SELECT group_concat(tag, char(31)) FROM link_tags WHERE link_tags.link = links.link), '')";
+
+fn link_from_row(row: &rusqlite::Row) -> rusqlite::Result<Link> {
+    let tags: String = row.get(10)?;
+    let mut tags: Vec<String> = tags
+        .split('\u{1f}')
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| tag.to_owned())
+        .collect();
+    tags.sort();
+
+    Ok(Link {
+        link: row.get(0)?,
+        title: row.get(1)?,
+        notes: row.get(2)?,
+        solved_count: row.get(3)?,
+        failed_count: row.get(7)?,
+        state: LinkState::from_flags(row.get(4)?, row.get(5)?, row.get(8)?),
+        completed_at: row.get(6)?,
+        skipped_until: row.get(9)?,
+        tags,
+    })
+}
+
+/// increase the solved count of the link and mark it as completed, clearing a failed attempt
+const MARK_AS_COMPLETE: &str = "UPDATE links
+    SET solved_count = solved_count + 1, is_solved = 1, is_failed = 0, completed_at = datetime('now')
+    WHERE link = ?1";
+
+/// increase the failed count of the link and put it back into the pool ahead of pending links
+const MARK_AS_FAILED: &str = "UPDATE links
+    SET failed_count = failed_count + 1, is_failed = 1, is_solved = 0
+    WHERE link = ?1";
+
+/// skip the link until the given number of days has passed, or until a reset when None
+const SKIP_LINK: &str = "UPDATE links
+    SET is_skipped = 1, skipped_at = datetime('now'),
+        skipped_until = CASE WHEN ?2 IS NULL THEN NULL ELSE datetime('now', '+' || ?2 || ' days') END
+    WHERE link = ?1";
+
+/// copy the db file into memory without writing to it, so files of other users,
+/// read-only files and files of older versions can be read alike
+fn copy_read_only(path: &Path) -> Result<rusqlite::Connection, CustomErrors> {
+    let source = match rusqlite::Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    ) {
+        Ok(val) => val,
+        Err(e) => return Err(CustomErrors::DBConnectionFailed(e)),
+    };
+    let mut copy = match rusqlite::Connection::open_in_memory() {
+        Ok(val) => val,
+        Err(e) => return Err(CustomErrors::DBConnectionFailed(e)),
+    };
+
+    // a single step of all pages, the copy can't be busy or locked
+    let result = Backup::new(&source, &mut copy).and_then(|backup| backup.step(-1));
+    match result {
+        Ok(StepResult::Done) => Ok(copy),
+        Ok(_) => Err(CustomErrors::Others(format!(
+            "The db {} is locked by another process",
+            path.display()
+        ))),
+        Err(e) => Err(CustomErrors::Query(
+            format!("Couldn't read the db {}", path.display()),
+            e,
+        )),
+    }
+}
+
+/// SET clause that takes a link out of the skipped state along with its snooze
+const CLEAR_SKIPPED: &str = "is_skipped = 0, skipped_at = NULL, skipped_until = NULL";
+
+/// longest snooze in days, sqlite can't compute dates much further out
+pub const MAX_SNOOZE_DAYS: u32 = 3650;
+
+/// add an entry to the practice history of the link
+fn record_history(conn: &rusqlite::Connection, link: &str, action: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO history (link, action, at) VALUES (?1, ?2, datetime('now'))",
+        (link, action),
+    )?;
+    Ok(())
+}
+
+/// add the records to the db inside the transaction, see `Db::merge_records`
+fn merge_records_in(
+    tx: &rusqlite::Transaction,
+    local: &HashMap<String, LinkRecord>,
+    links: &[LinkRecord],
+    collections: &[CollectionRecord],
+    policy: MergePolicy,
+) -> rusqlite::Result<SyncSummary> {
+    let mut summary = SyncSummary::default();
+
+    for remote in links {
+        let mut changed = false;
+
+        match local.get(&remote.link) {
+            Some(current) => {
+                let merged = policy.merge(current, remote);
+                if merged != *current {
+                    tx.execute(
+                        "UPDATE links
+                        SET title = ?2, notes = ?3, solved_count = ?4, is_solved = ?5,
+                            is_skipped = ?6, completed_at = ?7, failed_count = ?8, is_failed = ?9,
+                            skipped_until = ?10
+                        WHERE link = ?1",
+                        (
+                            &merged.link,
+                            &merged.title,
+                            &merged.notes,
+                            merged.solved_count,
+                            merged.completed,
+                            merged.skipped,
+                            &merged.completed_at,
+                            merged.failed_count,
+                            merged.failed,
+                            &merged.skipped_until,
+                        ),
+                    )?;
+                    changed = true;
+                }
+            }
+            None => {
+                tx.execute(
+                    "INSERT INTO links
+                        (link, title, notes, solved_count, is_solved, is_skipped, completed_at,
+                            failed_count, is_failed, skipped_until)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    (
+                        &remote.link,
+                        &remote.title,
+                        &remote.notes,
+                        remote.solved_count,
+                        remote.completed,
+                        remote.skipped,
+                        &remote.completed_at,
+                        remote.failed_count,
+                        remote.failed,
+                        &remote.skipped_until,
+                    ),
+                )?;
+                summary.added_links += 1;
+            }
+        };
+
+        for tag in &remote.tags {
+            changed |= tx.execute(
+                "INSERT OR IGNORE INTO link_tags (link, tag) VALUES (?1, ?2)",
+                (&remote.link, tag),
+            )? > 0;
+        }
+
+        let current_history = local.get(&remote.link).map(|record| &record.history);
+        for entry in &remote.history {
+            if current_history.is_some_and(|history| history.contains(entry)) {
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO history (link, action, at, duration_secs) VALUES (?1, ?2, ?3, ?4)",
+                (&remote.link, &entry.action, &entry.at, entry.duration_secs),
+            )?;
+            summary.added_history += 1;
+            changed = true;
+        }
+
+        if changed && local.contains_key(&remote.link) {
+            summary.updated_links += 1;
+        }
+        if changed || !local.contains_key(&remote.link) {
+            summary.links.push(remote.link.clone());
+        }
+    }
+
+    for collection in collections {
+        tx.execute(
+            "INSERT OR IGNORE INTO collections (name) VALUES (?1)",
+            [&collection.name],
+        )?;
+        for link in &collection.links {
+            summary.added_collection_links += tx.execute(
+                "INSERT OR IGNORE INTO collection_links (collection, link, position)
+                SELECT ?1, ?2, COALESCE(MAX(position), 0) + 1
+                FROM collection_links WHERE collection = ?1",
+                (&collection.name, link),
+            )?;
+        }
+    }
+
+    Ok(summary)
+}
+
+/// how long to wait for a lock held by another connection before giving up
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// struct to carry the db connection
 pub struct Db {
     conn: rusqlite::Connection,
+    hooks: Hooks,
+    webhooks: Webhooks,
+    /// failures of hooks and webhooks, they don't fail the change that triggered them
+    warnings: RefCell<Vec<String>>,
 }
 
 impl Db {
-    /// create a new connection
-    pub fn new(conn: rusqlite::Connection) -> Self {
-        Db { conn }
+    /// open the db file in the cache directory, creating it when missing
+    pub fn open_default() -> Result<Self, CustomErrors> {
+        Db::open(&create_db_file()?)
     }
 
-    /// get total, completed, and skipped links count
-    pub fn get_status(&self) -> Result<Option<(i32, i32, i32)>, CustomErrors> {
-        let mut stmt = match self.conn.prepare(
-            "SELECT
-                COALESCE(COUNT(*), 0) AS total_links,
-                COALESCE(SUM(CASE WHEN is_solved = 1 THEN 1 ELSE 0 END), 0) AS completed_links,
-                COALESCE(SUM(CASE WHEN is_skipped = 1 THEN 1 ELSE 0 END), 0) AS skipped_links
-            FROM links",
+    /// open the db file at the given path and create any missing tables
+    pub fn open(path: &Path) -> Result<Self, CustomErrors> {
+        match rusqlite::Connection::open(path) {
+            Ok(conn) => Db::from_connection(conn),
+            Err(e) => Err(CustomErrors::DBConnectionFailed(e)),
+        }
+    }
+
+    /// open the db file in the cache directory without creating or upgrading it,
+    /// for quick reads like shell completions, fails when the file doesn't exist
+    pub fn open_default_read_only() -> Result<Self, CustomErrors> {
+        let conn = match rusqlite::Connection::open_with_flags(
+            db_file_path()?,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
         ) {
             Ok(val) => val,
-            Err(_) => return Err(CustomErrors::StatementFailed),
+            Err(e) => return Err(CustomErrors::DBConnectionFailed(e)),
+        };
+
+        Ok(Db {
+            conn,
+            hooks: Hooks::default(),
+            webhooks: Webhooks::default(),
+            warnings: RefCell::new(vec![]),
+        })
+    }
+
+    /// open a throwaway in-memory db, handy for tests and experiments
+    pub fn open_in_memory() -> Result<Self, CustomErrors> {
+        match rusqlite::Connection::open_in_memory() {
+            Ok(conn) => Db::from_connection(conn),
+            Err(e) => Err(CustomErrors::DBConnectionFailed(e)),
+        }
+    }
+
+    fn from_connection(conn: rusqlite::Connection) -> Result<Self, CustomErrors> {
+        // wait for short writes of another process, e.g. `abhyas serve`, instead of failing
+        if let Err(e) = conn.busy_timeout(BUSY_TIMEOUT) {
+            return Err(CustomErrors::DBConnectionFailed(e));
+        }
+        create_tables(&conn)?;
+        Ok(Db {
+            conn,
+            hooks: Hooks::default(),
+            webhooks: Webhooks::default(),
+            warnings: RefCell::new(vec![]),
+        })
+    }
+
+    /// run the given hook commands after links are added, completed, skipped, deleted or imported
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// post links that are added, completed or skipped and streak milestones to the given webhooks
+    pub fn with_webhooks(mut self, webhooks: Webhooks) -> Self {
+        self.webhooks = webhooks;
+        self
+    }
+
+    /// returns and clears the failures of hooks and webhooks since the last call,
+    /// it's up to the caller how to show them
+    pub fn take_warnings(&self) -> Vec<String> {
+        let mut warnings = self.warnings.take();
+        warnings.extend(self.webhooks.take_warnings());
+        warnings
+    }
+
+    fn warn(&self, warning: String) {
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// run the hook of the event with the details of the links and send its webhooks,
+    /// a failing hook or webhook never fails the change that was already saved, it
+    /// only adds a warning
+    fn run_hook(&self, event: HookEvent, links: Vec<Link>) {
+        if links.is_empty() {
+            return;
+        }
+        if let Err(e) = self.hooks.run(event, &links) {
+            self.warn(e.to_string());
+        }
+
+        if let Some(webhook_event) = WebhookEvent::from_hook(event) {
+            if self.webhooks.wants(webhook_event) {
+                let body = serde_json::json!({ "event": webhook_event, "links": links });
+                self.send_webhook(webhook_event, &body);
+            }
+        }
+        if event == HookEvent::Complete && self.webhooks.wants(WebhookEvent::Streak) {
+            self.send_streak_milestone(links.len());
+        }
+    }
+
+    /// send the queued webhook deliveries without waiting for their retry delays,
+    /// waiting for the endpoints
+    pub fn flush_webhooks(&self) -> Result<DeliverySummary, CustomErrors> {
+        self.webhooks.flush_now()
+    }
+
+    /// wait at most `timeout` for the webhooks being sent in the background, returns
+    /// false when some are still running, their events stay queued for the next flush
+    pub fn wait_for_webhooks(&self, timeout: Duration) -> bool {
+        self.webhooks.wait(timeout)
+    }
+
+    fn send_webhook(&self, event: WebhookEvent, body: &serde_json::Value) {
+        if let Err(e) = self.webhooks.send(event, body) {
+            self.warn(e.to_string());
+        }
+    }
+
+    /// send a `streak` event when the first completions of the day reach a milestone
+    fn send_streak_milestone(&self, completed: usize) {
+        // later completions of the same day don't change the streak
+        match self.get_completed_today() {
+            Ok(today) if today as usize == completed => (),
+            Ok(_) => return,
+            Err(e) => {
+                self.warn(e.to_string());
+                return;
+            }
         };
 
-        match stmt.query_row([], |row| {
-            let total_links: i32 = row.get(0)?;
-            let completed_links: i32 = row.get(1)?;
-            let skipped_links: i32 = row.get(2)?;
-            Ok((total_links, completed_links, skipped_links))
-        }) {
-            Ok((total_links, completed_links, skipped_links)) => {
-                Ok(Some((total_links, completed_links, skipped_links)))
+        match self.get_streak() {
+            Ok(days) if STREAK_MILESTONES.contains(&days) => {
+                let body = serde_json::json!({ "event": WebhookEvent::Streak, "days": days });
+                self.send_webhook(WebhookEvent::Streak, &body);
             }
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(_) => Err(CustomErrors::Others(
-                "Error: Something went wrong while checking the status".to_owned(),
-            )),
+            Ok(_) => (),
+            Err(e) => self.warn(e.to_string()),
+        };
+    }
+
+    /// returns the details of the links that still exist, only when the event has a hook
+    /// or a webhook
+    fn hook_links(&self, event: HookEvent, links: &[String]) -> Vec<Link> {
+        let webhook = match WebhookEvent::from_hook(event) {
+            Some(WebhookEvent::Complete) => {
+                self.webhooks.wants(WebhookEvent::Complete)
+                    || self.webhooks.wants(WebhookEvent::Streak)
+            }
+            Some(webhook_event) => self.webhooks.wants(webhook_event),
+            None => false,
+        };
+        if !self.hooks.is_set(event) && !webhook {
+            return vec![];
         }
+        links
+            .iter()
+            .filter_map(|link| self.get_link(link).ok().flatten())
+            .collect()
     }
 
     /// add new links into the db
@@ -47,22 +464,21 @@ impl Db {
             "INSERT INTO links (link,solved_count,is_solved,is_skipped) VALUES (?1,?2,?3,?4)",
             (&link, 0, 0, 0),
         ) {
-            Ok(_) => Ok(()),
+            Ok(_) => {
+                self.run_hook(HookEvent::Add, self.hook_links(HookEvent::Add, &[link]));
+                Ok(())
+            }
             Err(e) => match e {
-                rusqlite::Error::SqliteFailure(err, _) => {
+                rusqlite::Error::SqliteFailure(err, _)
                     if err.code == rusqlite::ErrorCode::ConstraintViolation
                         && (err.extended_code == SQLITE_CONSTRAINT_PRIMARYKEY
-                            || err.extended_code == SQLITE_CONSTRAINT_UNIQUE)
-                    {
-                        Err(CustomErrors::DuplicateLinkValue)
-                    } else {
-                        Err(CustomErrors::Others(
-                            "Error: Something went wrong while inserting link".to_owned(),
-                        ))
-                    }
+                            || err.extended_code == SQLITE_CONSTRAINT_UNIQUE) =>
+                {
+                    Err(CustomErrors::DuplicateLinkValue)
                 }
-                _ => Err(CustomErrors::Others(
-                    "Error: Something went wrong while inserting link".to_owned(),
+                _ => Err(CustomErrors::Query(
+                    "Something went wrong while inserting link".to_owned(),
+                    e,
                 )),
             },
         }
@@ -70,24 +486,15 @@ impl Db {
 
     /// delete link from the db
     pub fn delete_link(&self, link: String) -> Result<(), CustomErrors> {
-        match self
-            .conn
-            .execute("DELETE FROM links WHERE link = ?1", [&link])
-        {
-            Ok(_) => (),
-            Err(_) => {
-                return Err(CustomErrors::Others(
-                    "Error: Something went wrong while deleting the selected link".to_owned(),
-                ))
-            }
-        }
+        self.apply_bulk_action(&BulkAction::Delete, &[link])?;
         Ok(())
     }
 
+    /// returns every link in the db
     pub fn get_links(&self) -> Result<Vec<String>, CustomErrors> {
         let mut stmt = match self.conn.prepare("SELECT link FROM links") {
             Ok(val) => val,
-            Err(_) => return Err(CustomErrors::StatementFailed),
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
         };
 
         let rows = match stmt.query_map([], |row| row.get(0)) {
@@ -97,268 +504,1581 @@ impl Db {
                     return Err(CustomErrors::InvalidColumnName(e))
                 }
                 _ => {
-                    return Err(CustomErrors::Others(
-                        "Error: Something went wrong while reading all links".to_owned(),
+                    return Err(CustomErrors::Query(
+                        "Something went wrong while reading all links".to_owned(),
+                        e,
                     ))
                 }
             },
         };
 
-        let mut links: Vec<String> = Vec::new();
-        for link_result in rows {
-            links.push(link_result.unwrap())
+        match rows.collect::<Result<Vec<String>, _>>() {
+            Ok(links) => Ok(links),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading all links".to_owned(),
+                e,
+            )),
         }
-        Ok(links)
     }
 
-    pub fn get_single_link(&self) -> Result<Option<(String, i32)>, CustomErrors> {
-        let mut stmt = match self.conn.prepare(
-            "SELECT link, solved_count FROM links
-            WHERE is_solved = 0 AND is_skipped = 0
-            LIMIT 1;",
+    /// returns the first link that is neither completed nor skipped, failed links come first,
+    /// snoozed links whose time is up are back in the pool
+    pub fn get_single_link(&self) -> Result<Option<Link>, CustomErrors> {
+        self.unsnooze_expired()?;
+        match self.conn.query_row(
+            &format!(
+                "SELECT {} FROM links
+                WHERE links.is_solved = 0 AND links.is_skipped = 0
+                ORDER BY links.is_failed DESC, links.rowid
+                LIMIT 1;",
+                LINK_COLUMNS
+            ),
+            [],
+            link_from_row,
         ) {
-            Ok(val) => val,
-            Err(_) => return Err(CustomErrors::StatementFailed),
-        };
-
-        match stmt.query_row([], |row| {
-            let link: String = row.get(0)?;
-            let solved_count: i32 = row.get(1)?;
-            Ok((link, solved_count))
-        }) {
-            Ok((link, solved_count)) => Ok(Some((link, solved_count))),
+            Ok(link) => Ok(Some(link)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(_) => Err(CustomErrors::Others(
-                "Error: While fetching unsolved link".to_owned(),
-            )),
+            Err(e) => Err(CustomErrors::Query("While fetching a link".to_owned(), e)),
         }
     }
 
-    pub fn get_searched_link_count(&self, link: &str) -> Result<Option<i32>, CustomErrors> {
-        let mut stmt = match self.conn.prepare(
-            "SELECT solved_count FROM links
-            WHERE link = ?1;",
-        ) {
-            Ok(val) => val,
-            Err(_) => return Err(CustomErrors::StatementFailed),
+    /// returns up to `count` links picked by the strategy, only links with the tag when given
+    pub fn select_links(
+        &self,
+        strategy: SelectionStrategy,
+        tag: Option<String>,
+        count: usize,
+    ) -> Result<Vec<Link>, CustomErrors> {
+        self.unsnooze_expired()?;
+        let (state, sort) = match strategy {
+            SelectionStrategy::Next => (None, LinkSort::Added),
+            SelectionStrategy::Random => (None, LinkSort::Random),
+            SelectionStrategy::LeastSolved => (None, LinkSort::LeastSolved),
+            SelectionStrategy::Review => {
+                (Some(LinkState::Completed), LinkSort::LeastRecentlyCompleted)
+            }
         };
+        let filter = LinkFilter {
+            state,
+            tag,
+            ..Default::default()
+        };
+
+        // a link can be completed and skipped at once, skipped links are never picked
+        let mut links: Vec<Link> = self
+            .query_links(&filter, sort, None, 0)?
+            .into_iter()
+            .filter(|link| match strategy {
+                SelectionStrategy::Next | SelectionStrategy::Random => {
+                    matches!(link.state, LinkState::Pending | LinkState::Failed)
+                }
+                _ => link.state != LinkState::Skipped,
+            })
+            .collect();
+        if strategy == SelectionStrategy::Next {
+            // stable, so both groups keep the order they were added in
+            links.sort_by_key(|link| link.state != LinkState::Failed);
+        }
+        links.truncate(count);
+
+        Ok(links)
+    }
 
-        match stmt.query_row([&link], |row| {
-            let solved_count: i32 = row.get(0)?;
-            Ok(solved_count)
-        }) {
-            Ok(solved_count) => Ok(Some(solved_count)),
+    /// returns the link with all its details, or None if the link does not exist
+    pub fn get_link(&self, link: &str) -> Result<Option<Link>, CustomErrors> {
+        match self.conn.query_row(
+            &format!("SELECT {} FROM links WHERE links.link = ?1;", LINK_COLUMNS),
+            [link],
+            link_from_row,
+        ) {
+            Ok(val) => Ok(Some(val)),
             Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(_) => Err(CustomErrors::Others(
-                "Error: Something went wrong while fetching link count".to_owned(),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading the link details".to_owned(),
+                e,
             )),
         }
     }
 
+    /// increase the solved count and mark the link as completed
     pub fn mark_as_complete(&self, link: &str) -> Result<(), CustomErrors> {
-        match self.conn.execute(
-            "UPDATE links
-            SET solved_count = solved_count + 1, is_solved = 1
-            WHERE link = ?1;",
-            [&link],
-        ) {
-            Ok(_) => (),
-            Err(_) => {
-                return Err(CustomErrors::Others(
-                    "Error: Something went wrong while marking the given link as complete"
-                        .to_owned(),
-                ))
-            }
-        };
+        self.apply_bulk_action(&BulkAction::MarkAsComplete, &[link.to_owned()])?;
+        Ok(())
+    }
+
+    /// count a failed attempt and serve the link before the pending ones
+    pub fn mark_as_failed(&self, link: &str) -> Result<(), CustomErrors> {
+        self.apply_bulk_action(&BulkAction::MarkAsFailed, &[link.to_owned()])?;
+        Ok(())
+    }
 
+    /// skip the link for the given number of days, after which it goes back to the pool
+    pub fn snooze_link(&self, link: &str, days: u32) -> Result<(), CustomErrors> {
+        self.apply_bulk_action(&BulkAction::Snooze(days), &[link.to_owned()])?;
         Ok(())
     }
 
+    /// put snoozed links whose time is up back into the pool, returns how many came back
+    pub fn unsnooze_expired(&self) -> Result<usize, CustomErrors> {
+        let result = self.conn.unchecked_transaction().and_then(|tx| {
+            tx.execute(
+                "INSERT INTO history (link, action, at)
+                SELECT link, 'unsnooze', datetime('now') FROM links
+                WHERE is_skipped = 1 AND skipped_until <= datetime('now')",
+                (),
+            )?;
+            let count = tx.execute(
+                &format!(
+                    "UPDATE links SET {}
+                    WHERE is_skipped = 1 AND skipped_until <= datetime('now')",
+                    CLEAR_SKIPPED
+                ),
+                (),
+            )?;
+            tx.commit().map(|_| count)
+        });
+
+        match result {
+            Ok(count) => Ok(count),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while returning snoozed links to the pool".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// mark the link as skipped
     pub fn skip_link(&self, link: &str) -> Result<(), CustomErrors> {
-        match self.conn.execute(
-            "
-            UPDATE links
-            SET is_skipped = 1
-            WHERE link = ?1;",
-            [&link],
-        ) {
-            Ok(_) => (),
-            Err(_) => {
-                return Err(CustomErrors::Others(
-                    "Error: Something went wrong while skipping the link".to_string(),
-                ))
+        self.apply_bulk_action(&BulkAction::Skip, &[link.to_owned()])?;
+        Ok(())
+    }
+
+    /// returns the links matching the filter in the given order, `limit` of None returns all
+    pub fn query_links(
+        &self,
+        filter: &LinkFilter,
+        sort: LinkSort,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<Link>, CustomErrors> {
+        let mut conditions = vec![];
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![];
+
+        match filter.state {
+            Some(LinkState::Pending) => conditions
+                .push("links.is_solved = 0 AND links.is_skipped = 0 AND links.is_failed = 0"),
+            Some(LinkState::Completed) => conditions.push("links.is_solved = 1"),
+            Some(LinkState::Skipped) => conditions.push("links.is_skipped = 1"),
+            Some(LinkState::Failed) => conditions
+                .push("links.is_failed = 1 AND links.is_solved = 0 AND links.is_skipped = 0"),
+            None => (),
+        };
+        if let Some(tag) = &filter.tag {
+            conditions.push("links.link IN (SELECT link FROM link_tags WHERE tag = ?)");
+            params.push(tag);
+        }
+        if let Some(collection) = &filter.collection {
+            conditions
+                .push("links.link IN (SELECT link FROM collection_links WHERE collection = ?)");
+            params.push(collection);
+        }
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        let order_clause = match sort {
+            LinkSort::Added if filter.collection.is_some() => {
+                params.extend(
+                    filter
+                        .collection
+                        .iter()
+                        .map(|val| val as &dyn rusqlite::ToSql),
+                );
+                "(SELECT position FROM collection_links
+                    WHERE collection_links.link = links.link AND collection = ?), links.rowid"
+            }
+            LinkSort::Added => "links.rowid",
+            LinkSort::Link => "links.link",
+            LinkSort::SolvedCount => "links.solved_count DESC, links.rowid",
+            LinkSort::CompletedAt => {
+                "links.completed_at IS NULL, links.completed_at DESC, links.rowid"
             }
+            LinkSort::LeastRecentlyCompleted => {
+                "links.completed_at IS NOT NULL, links.completed_at, links.rowid"
+            }
+            LinkSort::LeastSolved => "links.solved_count, links.failed_count DESC, random()",
+            LinkSort::Random => "random()",
         };
 
-        Ok(())
-    }
+        // sqlite treats a negative limit as no limit
+        let limit = limit.map(|val| val as i64).unwrap_or(-1);
+        let offset = offset as i64;
+        params.push(&limit);
+        params.push(&offset);
+
+        let query = format!(
+            "SELECT {} FROM links {} ORDER BY {} LIMIT ? OFFSET ?;",
+            LINK_COLUMNS, where_clause, order_clause
+        );
 
-    pub fn get_all_links(&self) -> Result<Option<Vec<(String, i32)>>, CustomErrors> {
-        let mut stmt = match self.conn.prepare("SELECT link, solved_count FROM links;") {
+        let mut stmt = match self.conn.prepare(&query) {
             Ok(val) => val,
-            Err(_) => return Err(CustomErrors::StatementFailed),
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
         };
 
-        let rows_iter = match stmt.query_map([], |row| {
-            let link: String = row.get(0)?;
-            let solved_count: i32 = row.get(1)?;
-            Ok((link, solved_count))
-        }) {
+        match stmt
+            .query_map(params.as_slice(), link_from_row)
+            .and_then(|rows| rows.collect::<Result<Vec<Link>, _>>())
+        {
+            Ok(links) => Ok(links),
+            Err(e) => Err(CustomErrors::Query("While fetching links".to_owned(), e)),
+        }
+    }
+
+    /// mark all skiped links as incomplete links
+    pub fn skipped_to_incomplete(&self) -> Result<usize, CustomErrors> {
+        self.reset_all("is_skipped", CLEAR_SKIPPED).map_err(|e| {
+            CustomErrors::Query(
+                "While trying to change all skipped links to incomplete".to_owned(),
+                e,
+            )
+        })
+    }
+
+    /// mark all completed links as incomplete links
+    pub fn completed_to_incomplete(&self) -> Result<usize, CustomErrors> {
+        self.reset_all("is_solved", "is_solved = 0").map_err(|e| {
+            CustomErrors::Query(
+                "While trying to change all completed links to incomplete".to_owned(),
+                e,
+            )
+        })
+    }
+
+    /// apply the SET clause to every link with the given state column set and record
+    /// the reset in the history
+    fn reset_all(&self, column: &str, set: &str) -> Result<usize, rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            &format!(
+                "INSERT INTO history (link, action, at)
+                SELECT link, 'reset', datetime('now') FROM links WHERE {} = 1",
+                column
+            ),
+            (),
+        )?;
+        let count = tx.execute(
+            &format!("UPDATE links SET {} WHERE {} = 1", set, column),
+            (),
+        )?;
+        tx.commit()?;
+        Ok(count)
+    }
+
+    /// add non-duplicate links from the file passed as argument, returns the inserted count
+    pub fn insert_links_from_file(&self, links: &[String]) -> Result<usize, CustomErrors> {
+        let tx = match self.conn.unchecked_transaction() {
             Ok(val) => val,
-            Err(_) => {
-                return Err(CustomErrors::Others(
-                    "Error: While fetching all links".to_owned(),
-                ))
-            }
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
         };
 
-        let mut links_solved_count_vec: Vec<(String, i32)> = vec![];
-        for row in rows_iter {
-            match row {
-                Ok(val) => links_solved_count_vec.push(val),
-                Err(_) => {
-                    return Err(CustomErrors::Others(
-                        "Error: While fetching unsolved link".to_owned(),
+        let mut inserted = vec![];
+        for link in links {
+            match tx.execute(
+                "INSERT OR IGNORE INTO links (link, solved_count, is_solved, is_skipped)
+                VALUES (?1, 0, 0, 0)",
+                [link],
+            ) {
+                Ok(val) if val > 0 => inserted.push(link.clone()),
+                Ok(_) => (),
+                Err(e) => {
+                    return Err(CustomErrors::Query(
+                        "Something went wrong while inserting links from file".to_owned(),
+                        e,
                     ))
                 }
             };
         }
 
-        if links_solved_count_vec.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(links_solved_count_vec))
+        match tx.commit() {
+            Ok(_) => {
+                self.run_hook(
+                    HookEvent::Import,
+                    self.hook_links(HookEvent::Import, &inserted),
+                );
+                Ok(inserted.len())
+            }
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while inserting links from file".to_owned(),
+                e,
+            )),
         }
     }
 
-    /// returns only the completed links along with their solved_count
-    pub fn get_completed_links(&self) -> Result<Option<Vec<(String, i32)>>, CustomErrors> {
-        let mut stmt = match self
-            .conn
-            .prepare("SELECT link, solved_count FROM links where is_solved = 1;")
-        {
+    /// apply the given action to every selected link inside a single transaction
+    pub fn apply_bulk_action(
+        &self,
+        action: &BulkAction,
+        links: &[String],
+    ) -> Result<usize, CustomErrors> {
+        if let BulkAction::Snooze(days) = action {
+            if *days == 0 || *days > MAX_SNOOZE_DAYS {
+                return Err(CustomErrors::InvalidInput(format!(
+                    "Snooze for 1 to {} days",
+                    MAX_SNOOZE_DAYS
+                )));
+            }
+        }
+
+        let tx = match self.conn.unchecked_transaction() {
             Ok(val) => val,
-            Err(_) => return Err(CustomErrors::StatementFailed),
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
         };
 
-        let rows_iter = match stmt.query_map([], |row| {
-            let link: String = row.get(0)?;
-            let solved_count: i32 = row.get(1)?;
-            Ok((link, solved_count))
-        }) {
-            Ok(val) => val,
-            Err(_) => {
-                return Err(CustomErrors::Others(
-                    "Error: While fetching completed links".to_owned(),
-                ))
+        if let BulkAction::MoveToCollection(collection) = action {
+            if let Err(e) = tx.execute(
+                "INSERT OR IGNORE INTO collections (name) VALUES (?1)",
+                [collection],
+            ) {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while creating the collection".to_owned(),
+                    e,
+                ));
             }
+        }
+
+        let event = match action {
+            BulkAction::Delete => Some(HookEvent::Delete),
+            BulkAction::Skip | BulkAction::Snooze(_) => Some(HookEvent::Skip),
+            BulkAction::MarkAsComplete => Some(HookEvent::Complete),
+            _ => None,
+        };
+        // deleted links are gone after the commit, so their details are read up front
+        let deleted = match action {
+            BulkAction::Delete => self.hook_links(HookEvent::Delete, links),
+            _ => vec![],
         };
 
-        let mut links_completed_vec: Vec<(String, i32)> = vec![];
-        for row in rows_iter {
-            match row {
-                Ok(val) => links_completed_vec.push(val),
-                Err(_) => {
-                    return Err(CustomErrors::Others(
-                        "Error: While fetching completed link".to_owned(),
-                    ))
+        let mut changed_count = 0;
+        let mut changed_links = vec![];
+        for link in links {
+            let result = match action {
+                BulkAction::Delete => tx
+                    .execute("DELETE FROM history WHERE link = ?1", [link])
+                    .and_then(|_| tx.execute("DELETE FROM link_tags WHERE link = ?1", [link]))
+                    .and_then(|_| {
+                        tx.execute("DELETE FROM collection_links WHERE link = ?1", [link])
+                    })
+                    .and_then(|_| tx.execute("DELETE FROM links WHERE link = ?1", [link])),
+                BulkAction::Skip => tx.execute(SKIP_LINK, (link, None::<u32>)),
+                BulkAction::Snooze(days) => tx.execute(SKIP_LINK, (link, days)),
+                BulkAction::MarkAsComplete => tx.execute(MARK_AS_COMPLETE, [link]),
+                BulkAction::MarkAsFailed => tx.execute(MARK_AS_FAILED, [link]),
+                BulkAction::Reset => tx.execute(
+                    &format!(
+                        "UPDATE links SET is_solved = 0, is_failed = 0, {} WHERE link = ?1",
+                        CLEAR_SKIPPED
+                    ),
+                    [link],
+                ),
+                BulkAction::Tag(tag) => tx.execute(
+                    "INSERT OR IGNORE INTO link_tags (link, tag) VALUES (?1, ?2)",
+                    (link, tag),
+                ),
+                BulkAction::MoveToCollection(collection) => tx
+                    .execute(
+                        "DELETE FROM collection_links WHERE link = ?1 AND collection != ?2",
+                        (link, collection),
+                    )
+                    .and_then(|_| {
+                        tx.execute(
+                            "INSERT OR IGNORE INTO collection_links (collection, link, position)
+                            SELECT ?1, ?2, COALESCE(MAX(position), 0) + 1
+                            FROM collection_links WHERE collection = ?1",
+                            (collection, link),
+                        )
+                    }),
+            };
+
+            let history_action = match action {
+                BulkAction::Skip | BulkAction::Snooze(_) => Some("skip"),
+                BulkAction::MarkAsComplete => Some("complete"),
+                BulkAction::MarkAsFailed => Some("fail"),
+                BulkAction::Reset => Some("reset"),
+                _ => None,
+            };
+
+            let result = match (result, history_action) {
+                (Ok(count), Some(history_action)) if count > 0 => {
+                    record_history(&tx, link, history_action).map(|_| count)
+                }
+                (result, _) => result,
+            };
+
+            match result {
+                Ok(val) => {
+                    if val > 0 {
+                        changed_links.push(link.clone());
+                    }
+                    changed_count += val
                 }
+                Err(e) => return Err(CustomErrors::Query(
+                    "Something went wrong while applying the bulk action, no links were changed"
+                        .to_owned(),
+                    e,
+                )),
             };
         }
 
-        if links_completed_vec.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(links_completed_vec))
+        if let Err(e) = tx.commit() {
+            return Err(CustomErrors::Query(
+                "Something went wrong while saving the bulk action".to_owned(),
+                e,
+            ));
         }
+
+        match event {
+            Some(HookEvent::Delete) => self.run_hook(
+                HookEvent::Delete,
+                deleted
+                    .into_iter()
+                    .filter(|link| changed_links.contains(&link.link))
+                    .collect(),
+            ),
+            Some(event) => self.run_hook(event, self.hook_links(event, &changed_links)),
+            None => (),
+        };
+
+        Ok(changed_count)
     }
 
-    /// returns only the skipped links along with their solved_count
-    pub fn get_skipped_links(&self) -> Result<Option<Vec<(String, i32)>>, CustomErrors> {
+    /// returns all tags in alphabetical order
+    pub fn get_tags(&self) -> Result<Vec<String>, CustomErrors> {
         let mut stmt = match self
             .conn
-            .prepare("SELECT link, solved_count from links WHERE is_skipped = 1;")
+            .prepare("SELECT DISTINCT tag FROM link_tags ORDER BY tag;")
         {
             Ok(val) => val,
-            Err(_) => return Err(CustomErrors::StatementFailed),
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
         };
 
-        let rows_iter = match stmt.query_map([], |row| {
-            let link: String = row.get(0)?;
-            let solved_count: i32 = row.get(1)?;
-            Ok((link, solved_count))
-        }) {
+        let rows = match stmt.query_map([], |row| row.get(0)) {
             Ok(val) => val,
-            Err(_) => {
-                return Err(CustomErrors::Others(
-                    "Error: While fetching all skipped links".to_owned(),
+            Err(e) => {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while reading tags".to_owned(),
+                    e,
                 ))
             }
         };
 
-        let mut skipped_links_vec: Vec<(String, i32)> = vec![];
-        for row in rows_iter {
-            match row {
-                Ok(val) => skipped_links_vec.push(val),
-                Err(_) => {
-                    return Err(CustomErrors::Others(
-                        "Error: While fetching all skipped links".to_owned(),
-                    ))
-                }
-            };
-        }
-
-        if skipped_links_vec.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(skipped_links_vec))
+        match rows.collect::<Result<Vec<String>, _>>() {
+            Ok(tags) => Ok(tags),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading tags".to_owned(),
+                e,
+            )),
         }
     }
 
-    /// mark all skiped links as incomplete links
-    pub fn skipped_to_incomplete(&self) -> Result<usize, CustomErrors> {
-        self.conn
-            .execute("UPDATE links SET is_skipped = 0 WHERE is_skipped = 1;", ())
-            .map_err(|_| {
-                CustomErrors::Others(
-                    "Error: While trying to change all skipped links to incomplete".to_owned(),
-                )
-            })
-    }
+    /// returns the links that the given reset filter would change
+    pub fn get_links_to_reset(&self, filter: &ResetFilter) -> Result<Vec<String>, CustomErrors> {
+        let (reset_completed, reset_skipped) = match (filter.completed, filter.skipped) {
+            (false, false) => (true, true),
+            value => value,
+        };
 
-    /// mark all completed links as incomplete links
-    pub fn completed_to_incomplete(&self) -> Result<usize, CustomErrors> {
-        self.conn
-            .execute("UPDATE links SET is_solved = 0 WHERE is_solved = 1;", ())
-            .map_err(|_| {
-                CustomErrors::Others(
-                    "Error: While trying to change all completed links to incomplete".to_owned(),
-                )
-            })
-    }
+        let mut states = vec![];
+        if reset_completed {
+            states.push("is_solved = 1");
+        }
+        if reset_skipped {
+            states.push("is_skipped = 1");
+        }
 
-    /// add non-duplicate links from the file passed as argument
-    pub fn insert_links_from_file(&self, links: &[String]) -> Result<usize, CustomErrors> {
-        let values: String = links
-            .iter()
-            .map(|link| format!("('{}', 0, 0, 0)", link))
-            .collect::<Vec<String>>()
-            .join(",");
+        let mut conditions = vec![format!("({})", states.join(" OR "))];
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![];
+
+        if let Some(tag) = &filter.tag {
+            conditions.push("link IN (SELECT link FROM link_tags WHERE tag = ?)".to_owned());
+            params.push(tag);
+        }
+        if let Some(collection) = &filter.collection {
+            conditions.push(
+                "link IN (SELECT link FROM collection_links WHERE collection = ?)".to_owned(),
+            );
+            params.push(collection);
+        }
+        if let Some(date) = &filter.completed_before {
+            // links completed before completed_at was recorded have it unset, they were
+            // completed before any date that can be given
+            conditions.push(
+                "(date(completed_at) < date(?) OR completed_at IS NULL AND solved_count > 0)"
+                    .to_owned(),
+            );
+            params.push(date);
+        }
+        if let Some(count) = &filter.solved_below {
+            conditions.push("solved_count < ?".to_owned());
+            params.push(count);
+        }
+        if !filter.links.is_empty() {
+            let placeholders = vec!["?"; filter.links.len()].join(", ");
+            conditions.push(format!("link IN ({})", placeholders));
+            for link in &filter.links {
+                params.push(link);
+            }
+        }
 
         let query = format!(
-            "INSERT OR IGNORE INTO links (link, solved_count, is_solved, is_skipped) VALUES {}",
-            values
+            "SELECT link FROM links WHERE {} ORDER BY link;",
+            conditions.join(" AND ")
         );
 
-        let rows_updated_count = match self.conn.execute(&query, ()) {
+        let mut stmt = match self.conn.prepare(&query) {
             Ok(val) => val,
-            Err(_) => {
-                return Err(CustomErrors::Others(
-                    "Error: Something went wrong while inserting links from file".to_owned(),
-                ));
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        let rows = match stmt.query_map(params.as_slice(), |row| row.get::<_, String>(0)) {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while finding links to reset".to_owned(),
+                    e,
+                ))
             }
         };
 
-        Ok(rows_updated_count)
+        let mut links = vec![];
+        for row in rows {
+            match row {
+                Ok(link) => {
+                    let keep = match &filter.platform {
+                        Some(platform) => platform_matches(&link, platform),
+                        None => true,
+                    };
+                    if keep {
+                        links.push(link);
+                    }
+                }
+                Err(e) => {
+                    return Err(CustomErrors::Query(
+                        "Something went wrong while finding links to reset".to_owned(),
+                        e,
+                    ))
+                }
+            };
+        }
+
+        Ok(links)
+    }
+
+    /// change the links matched by the filter back to incomplete, returns the changed links
+    pub fn reset_links(&self, filter: &ResetFilter) -> Result<Vec<String>, CustomErrors> {
+        let links = self.get_links_to_reset(filter)?;
+
+        let tx = match self.conn.unchecked_transaction() {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        let query = match (filter.completed, filter.skipped) {
+            (true, false) => "UPDATE links SET is_solved = 0 WHERE link = ?1".to_owned(),
+            (false, true) => format!("UPDATE links SET {} WHERE link = ?1", CLEAR_SKIPPED),
+            _ => format!(
+                "UPDATE links SET is_solved = 0, {} WHERE link = ?1",
+                CLEAR_SKIPPED
+            ),
+        };
+
+        for link in &links {
+            if let Err(e) = tx
+                .execute(&query, [link])
+                .and_then(|_| record_history(&tx, link, "reset"))
+            {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while resetting links, no links were changed".to_owned(),
+                    e,
+                ));
+            }
+        }
+
+        match tx.commit() {
+            Ok(_) => Ok(links),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while saving the reset links".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// returns all collection names in alphabetical order
+    pub fn get_collections(&self) -> Result<Vec<String>, CustomErrors> {
+        let mut stmt = match self
+            .conn
+            .prepare("SELECT name FROM collections ORDER BY name;")
+        {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        match stmt
+            .query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect::<Result<Vec<String>, _>>())
+        {
+            Ok(collections) => Ok(collections),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading collections".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// append the links to the end of the collection, creating it if needed
+    pub fn add_links_to_collection(
+        &self,
+        collection: &str,
+        links: &[String],
+    ) -> Result<usize, CustomErrors> {
+        let tx = match self.conn.unchecked_transaction() {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        if let Err(e) = tx.execute(
+            "INSERT OR IGNORE INTO collections (name) VALUES (?1)",
+            [collection],
+        ) {
+            return Err(CustomErrors::Query(
+                "Something went wrong while creating the collection".to_owned(),
+                e,
+            ));
+        }
+
+        let mut added_count = 0;
+        for link in links {
+            match tx.execute(
+                "INSERT OR IGNORE INTO collection_links (collection, link, position)
+                SELECT ?1, ?2, COALESCE(MAX(position), 0) + 1
+                FROM collection_links WHERE collection = ?1",
+                (collection, link),
+            ) {
+                Ok(val) => added_count += val,
+                Err(e) => {
+                    return Err(CustomErrors::Query(
+                        "Something went wrong while adding links to the collection".to_owned(),
+                        e,
+                    ))
+                }
+            };
+        }
+
+        match tx.commit() {
+            Ok(_) => Ok(added_count),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while saving the collection".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// returns the first incomplete and unskipped link of the collection in order,
+    /// failed links come first
+    pub fn get_next_collection_link(&self, collection: &str) -> Result<Option<Link>, CustomErrors> {
+        self.unsnooze_expired()?;
+        let mut stmt = match self.conn.prepare(&format!(
+            "SELECT {} FROM collection_links
+            JOIN links ON links.link = collection_links.link
+            WHERE collection_links.collection = ?1
+                AND links.is_solved = 0 AND links.is_skipped = 0
+            ORDER BY links.is_failed DESC, collection_links.position
+            LIMIT 1;",
+            LINK_COLUMNS
+        )) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        match stmt.query_row([collection], link_from_row) {
+            Ok(link) => Ok(Some(link)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(CustomErrors::Query(
+                "While fetching the next link of the collection".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// get total, completed, and skipped links count for every collection
+    pub fn get_collection_status(&self) -> Result<Vec<CollectionStatus>, CustomErrors> {
+        let mut stmt = match self.conn.prepare(
+            "SELECT
+                collections.name,
+                COUNT(links.link) AS total_links,
+                COALESCE(SUM(CASE WHEN links.is_solved = 1 THEN 1 ELSE 0 END), 0) AS completed_links,
+                COALESCE(SUM(CASE WHEN links.is_skipped = 1 THEN 1 ELSE 0 END), 0) AS skipped_links
+            FROM collections
+            LEFT JOIN collection_links ON collection_links.collection = collections.name
+            LEFT JOIN links ON links.link = collection_links.link
+            GROUP BY collections.name
+            ORDER BY collections.name;",
+        ) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        match stmt
+            .query_map([], |row| {
+                Ok(CollectionStatus {
+                    name: row.get(0)?,
+                    total: row.get(1)?,
+                    completed: row.get(2)?,
+                    skipped: row.get(3)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(status) => Ok(status),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while checking the collection status".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// update the title and notes of the link
+    pub fn update_link_details(
+        &self,
+        link: &str,
+        title: &str,
+        notes: &str,
+    ) -> Result<(), CustomErrors> {
+        match self.conn.execute(
+            "UPDATE links SET title = ?2, notes = ?3 WHERE link = ?1;",
+            (link, title, notes),
+        ) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while updating the link details".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// search links through the full-text index, best matches first
+    pub fn search_links(
+        &self,
+        query: &SearchQuery,
+        limit: usize,
+    ) -> Result<Vec<Link>, CustomErrors> {
+        let match_expression = query.match_expression();
+
+        let mut conditions = vec![];
+        let mut params: Vec<&dyn rusqlite::ToSql> = vec![];
+
+        if let Some(expression) = &match_expression {
+            conditions.push("links_fts MATCH ?");
+            params.push(expression);
+        }
+        for tag in &query.tags {
+            conditions.push("links.link IN (SELECT link FROM link_tags WHERE tag = ?)");
+            params.push(tag);
+        }
+        if let Some(collection) = &query.collection {
+            conditions
+                .push("links.link IN (SELECT link FROM collection_links WHERE collection = ?)");
+            params.push(collection);
+        }
+        match query.status {
+            Some(StatusFilter::Pending) => conditions
+                .push("links.is_solved = 0 AND links.is_skipped = 0 AND links.is_failed = 0"),
+            Some(StatusFilter::Completed) => conditions.push("links.is_solved = 1"),
+            Some(StatusFilter::Skipped) => conditions.push("links.is_skipped = 1"),
+            Some(StatusFilter::Failed) => conditions
+                .push("links.is_failed = 1 AND links.is_solved = 0 AND links.is_skipped = 0"),
+            None => (),
+        };
+
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+
+        // the platform filter runs on the parsed host, so the limit is applied afterwards
+        let limit_clause = match query.platform {
+            Some(_) => String::new(),
+            None => format!("LIMIT {}", limit),
+        };
+
+        let sql = match match_expression {
+            Some(_) => format!(
+                "SELECT {} FROM links_fts
+                JOIN links ON links.rowid = links_fts.rowid
+                {} ORDER BY links_fts.rank {};",
+                LINK_COLUMNS, where_clause, limit_clause
+            ),
+            None => format!(
+                "SELECT {} FROM links {} ORDER BY links.link {};",
+                LINK_COLUMNS, where_clause, limit_clause
+            ),
+        };
+
+        let mut stmt = match self.conn.prepare(&sql) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        let rows = match stmt
+            .query_map(params.as_slice(), link_from_row)
+            .and_then(|rows| rows.collect::<Result<Vec<Link>, _>>())
+        {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while searching links".to_owned(),
+                    e,
+                ))
+            }
+        };
+
+        match &query.platform {
+            Some(platform) => Ok(rows
+                .into_iter()
+                .filter(|link| platform_matches(&link.link, platform))
+                .take(limit)
+                .collect()),
+            None => Ok(rows),
+        }
+    }
+
+    /// returns the practice history of the link, newest first
+    pub fn get_link_history(&self, link: &str) -> Result<Vec<(String, String)>, CustomErrors> {
+        let mut stmt = match self
+            .conn
+            .prepare("SELECT action, at FROM history WHERE link = ?1 ORDER BY at DESC, rowid DESC;")
+        {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        match stmt
+            .query_map([link], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(history) => Ok(history),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading the link history".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// get total, completed, skipped, failed and pending links count
+    pub fn get_status_summary(&self) -> Result<Status, CustomErrors> {
+        match self.conn.query_row(
+            "SELECT
+                COUNT(*),
+                COALESCE(SUM(CASE WHEN is_solved = 1 THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN is_skipped = 1 THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN is_failed = 1 AND is_solved = 0 AND is_skipped = 0
+                    THEN 1 ELSE 0 END), 0),
+                COALESCE(SUM(CASE WHEN is_solved = 0 AND is_skipped = 0 AND is_failed = 0
+                    THEN 1 ELSE 0 END), 0)
+            FROM links",
+            [],
+            |row| {
+                Ok(Status {
+                    total: row.get(0)?,
+                    completed: row.get(1)?,
+                    skipped: row.get(2)?,
+                    failed: row.get(3)?,
+                    pending: row.get(4)?,
+                })
+            },
+        ) {
+            Ok(status) => Ok(status),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while checking the status".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// returns how many links were completed and skipped on each day of the last year
+    pub fn get_activity(&self) -> Result<Vec<DayActivity>, CustomErrors> {
+        let mut stmt = match self.conn.prepare(
+            "SELECT
+                date(at) AS day,
+                SUM(CASE WHEN action = 'complete' THEN 1 ELSE 0 END) AS completed,
+                SUM(CASE WHEN action = 'skip' THEN 1 ELSE 0 END) AS skipped
+            FROM history
+            WHERE at >= datetime('now', '-1 year') AND action IN ('complete', 'skip')
+            GROUP BY day
+            ORDER BY day;",
+        ) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        match stmt
+            .query_map([], |row| {
+                Ok(DayActivity {
+                    date: row.get(0)?,
+                    completed: row.get(1)?,
+                    skipped: row.get(2)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(activity) => Ok(activity),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading the practice activity".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// mark the link as completed and record how long the attempt took
+    pub fn mark_as_complete_timed(
+        &self,
+        link: &str,
+        duration_secs: i64,
+    ) -> Result<(), CustomErrors> {
+        let result = self.conn.unchecked_transaction().and_then(|tx| {
+            if tx.execute(MARK_AS_COMPLETE, [link])? > 0 {
+                tx.execute(
+                    "INSERT INTO history (link, action, at, duration_secs)
+                    VALUES (?1, 'complete', datetime('now'), ?2)",
+                    (link, duration_secs),
+                )?;
+            }
+            tx.commit()
+        });
+
+        match result {
+            Ok(_) => {
+                let links = self.hook_links(HookEvent::Complete, &[link.to_owned()]);
+                self.run_hook(HookEvent::Complete, links);
+                Ok(())
+            }
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while marking the link as completed".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// returns the average and best time of every link with a timed completion
+    pub fn get_link_times(&self) -> Result<Vec<SolveTime>, CustomErrors> {
+        self.read_solve_times(
+            "SELECT link, COUNT(*), CAST(AVG(duration_secs) AS INTEGER), MIN(duration_secs)
+            FROM history
+            WHERE action = 'complete' AND duration_secs IS NOT NULL
+            GROUP BY link
+            ORDER BY link;",
+        )
+    }
+
+    /// returns the average and best time of every tag with a timed completion
+    pub fn get_tag_times(&self) -> Result<Vec<SolveTime>, CustomErrors> {
+        self.read_solve_times(
+            "SELECT link_tags.tag, COUNT(*), CAST(AVG(history.duration_secs) AS INTEGER),
+                MIN(history.duration_secs)
+            FROM history
+            JOIN link_tags ON link_tags.link = history.link
+            WHERE history.action = 'complete' AND history.duration_secs IS NOT NULL
+            GROUP BY link_tags.tag
+            ORDER BY link_tags.tag;",
+        )
+    }
+
+    fn read_solve_times(&self, query: &str) -> Result<Vec<SolveTime>, CustomErrors> {
+        let mut stmt = match self.conn.prepare(query) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        match stmt
+            .query_map([], |row| {
+                Ok(SolveTime {
+                    name: row.get(0)?,
+                    attempts: row.get(1)?,
+                    average_secs: row.get(2)?,
+                    best_secs: row.get(3)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(times) => Ok(times),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading the solve times".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// store a new practice session and return its id
+    pub fn start_session(
+        &self,
+        minutes: u32,
+        strategy: SelectionStrategy,
+        tag: Option<&str>,
+    ) -> Result<i64, CustomErrors> {
+        match self.conn.execute(
+            "INSERT INTO sessions (started_at, minutes, strategy, tag)
+            VALUES (datetime('now'), ?1, ?2, ?3)",
+            (minutes, strategy.to_string(), tag),
+        ) {
+            Ok(_) => Ok(self.conn.last_insert_rowid()),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while starting the session".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// record how a link of the session ended, called after every link so an
+    /// interrupted session keeps what was practiced so far
+    pub fn record_session_link(
+        &self,
+        session_id: i64,
+        position: usize,
+        entry: &SessionLink,
+    ) -> Result<(), CustomErrors> {
+        match self.conn.execute(
+            "INSERT INTO session_links (session_id, position, link, outcome, duration_secs)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            (
+                session_id,
+                position as i64,
+                &entry.link,
+                entry.outcome.to_string(),
+                entry.duration_secs,
+            ),
+        ) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while saving the session".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// returns every session with its links, the most recent first
+    pub fn get_sessions(&self) -> Result<Vec<Session>, CustomErrors> {
+        self.read_sessions(None)
+    }
+
+    /// returns a single session with its links, or None if it does not exist
+    pub fn get_session(&self, id: i64) -> Result<Option<Session>, CustomErrors> {
+        Ok(self.read_sessions(Some(id))?.pop())
+    }
+
+    fn read_sessions(&self, id: Option<i64>) -> Result<Vec<Session>, CustomErrors> {
+        let result = self.conn.prepare(
+            "SELECT id, started_at, minutes, strategy, tag FROM sessions
+            WHERE ?1 IS NULL OR id = ?1
+            ORDER BY id DESC;",
+        );
+        let mut stmt = match result {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+        let sessions = match stmt
+            .query_map([id], |row| {
+                Ok(Session {
+                    id: row.get(0)?,
+                    started_at: row.get(1)?,
+                    minutes: row.get(2)?,
+                    strategy: row.get(3)?,
+                    tag: row.get(4)?,
+                    links: vec![],
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while reading the sessions".to_owned(),
+                    e,
+                ))
+            }
+        };
+
+        let mut stmt = match self.conn.prepare(
+            "SELECT link, outcome, duration_secs FROM session_links
+            WHERE session_id = ?1
+            ORDER BY position;",
+        ) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        sessions
+            .into_iter()
+            .map(|mut session| {
+                let links = stmt
+                    .query_map([session.id], |row| {
+                        let outcome: String = row.get(1)?;
+                        Ok(SessionLink {
+                            link: row.get(0)?,
+                            outcome: SessionOutcome::from_db(&outcome)
+                                .unwrap_or(SessionOutcome::Skipped),
+                            duration_secs: row.get(2)?,
+                        })
+                    })
+                    .and_then(|rows| rows.collect::<Result<Vec<_>, _>>());
+                match links {
+                    Ok(val) => {
+                        session.links = val;
+                        Ok(session)
+                    }
+                    Err(e) => Err(CustomErrors::Query(
+                        "Something went wrong while reading the session links".to_owned(),
+                        e,
+                    )),
+                }
+            })
+            .collect()
+    }
+
+    /// returns how many links were completed since the start of the current utc day
+    pub fn get_completed_today(&self) -> Result<i32, CustomErrors> {
+        match self.conn.query_row(
+            "SELECT COUNT(*) FROM history WHERE action = 'complete' AND at >= date('now')",
+            [],
+            |row| row.get(0),
+        ) {
+            Ok(count) => Ok(count),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while counting today's completions".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// returns the number of consecutive utc days with at least one completion,
+    /// ending today or yesterday when nothing was completed today yet
+    pub fn get_streak(&self) -> Result<i32, CustomErrors> {
+        let mut stmt = match self.conn.prepare(
+            "SELECT DISTINCT CAST(julianday('now', 'start of day') - julianday(date(at)) AS INTEGER)
+                AS days_ago
+            FROM history
+            WHERE action = 'complete'
+            ORDER BY days_ago;",
+        ) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        let days_ago = match stmt
+            .query_map([], |row| row.get::<_, i32>(0))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while reading the streak".to_owned(),
+                    e,
+                ))
+            }
+        };
+
+        let start = match days_ago.first() {
+            Some(&day) if day <= 1 => day,
+            _ => return Ok(0),
+        };
+
+        Ok(days_ago
+            .iter()
+            .enumerate()
+            .take_while(|(i, &day)| day == start + *i as i32)
+            .count() as i32)
+    }
+
+    /// returns the completed links that were last completed at least `days` ago,
+    /// the longest ago completed first
+    pub fn get_due_reviews(&self, days: u32) -> Result<Vec<Link>, CustomErrors> {
+        let mut stmt = match self.conn.prepare(&format!(
+            "SELECT {} FROM links
+            WHERE links.is_solved = 1 AND links.is_skipped = 0
+                AND links.completed_at <= datetime('now', '-' || ?1 || ' days')
+            ORDER BY links.completed_at, links.rowid;",
+            LINK_COLUMNS
+        )) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        match stmt
+            .query_map([days], link_from_row)
+            .and_then(|rows| rows.collect::<Result<Vec<Link>, _>>())
+        {
+            Ok(links) => Ok(links),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading the due reviews".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// returns every completed link that isn't skipped along with the `YYYY-MM-DD` date it
+    /// is due for review, `days` after its last completion, sorted by date and link
+    pub fn get_review_dates(&self, days: u32) -> Result<Vec<(Link, String)>, CustomErrors> {
+        let mut stmt = match self.conn.prepare(&format!(
+            "SELECT {}, date(links.completed_at, '+' || ?1 || ' days') AS due FROM links
+            WHERE links.is_solved = 1 AND links.is_skipped = 0 AND links.completed_at IS NOT NULL
+            ORDER BY due, links.link;",
+            LINK_COLUMNS
+        )) {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        match stmt
+            .query_map([days], |row| Ok((link_from_row(row)?, row.get(11)?)))
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+        {
+            Ok(reviews) => Ok(reviews),
+            Err(e) => Err(CustomErrors::Query(
+                "Something went wrong while reading the review dates".to_owned(),
+                e,
+            )),
+        }
+    }
+
+    /// returns the overall status, the progress of every collection and the recent activity
+    pub fn get_stats(&self) -> Result<Stats, CustomErrors> {
+        let status = self.get_status_summary()?;
+        Ok(Stats {
+            status,
+            collections: self.get_collection_status()?,
+            activity: self.get_activity()?,
+            link_times: self.get_link_times()?,
+            tag_times: self.get_tag_times()?,
+        })
+    }
+
+    /// returns every link with its tags and history, sorted by link
+    pub fn get_link_records(&self) -> Result<Vec<LinkRecord>, CustomErrors> {
+        self.read_link_records().map_err(|e| {
+            CustomErrors::Query(
+                "Something went wrong while reading the links to sync".to_owned(),
+                e,
+            )
+        })
+    }
+
+    fn read_link_records(&self) -> rusqlite::Result<Vec<LinkRecord>> {
+        let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT link, tag FROM link_tags ORDER BY tag")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))?;
+        for row in rows {
+            let (link, tag) = row?;
+            tags.entry(link).or_default().push(tag);
+        }
+
+        let mut history: HashMap<String, Vec<HistoryEntry>> = HashMap::new();
+        let mut stmt = self
+            .conn
+            .prepare("SELECT link, action, at, duration_secs FROM history ORDER BY at, action")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                HistoryEntry {
+                    action: row.get(1)?,
+                    at: row.get(2)?,
+                    duration_secs: row.get(3)?,
+                },
+            ))
+        })?;
+        for row in rows {
+            let (link, entry) = row?;
+            history.entry(link).or_default().push(entry);
+        }
+
+        let mut stmt = self.conn.prepare(
+            "SELECT link, title, notes, solved_count, is_solved, is_skipped, completed_at,
+                failed_count, is_failed, skipped_until
+            FROM links ORDER BY link",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            let link: String = row.get(0)?;
+            Ok(LinkRecord {
+                tags: tags.remove(&link).unwrap_or_default(),
+                history: history.remove(&link).unwrap_or_default(),
+                link,
+                title: row.get(1)?,
+                notes: row.get(2)?,
+                solved_count: row.get(3)?,
+                failed_count: row.get(7)?,
+                completed: row.get(4)?,
+                skipped: row.get(5)?,
+                failed: row.get(8)?,
+                completed_at: row.get(6)?,
+                skipped_until: row.get(9)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// returns every collection with its links in order, sorted by name
+    pub fn get_collection_records(&self) -> Result<Vec<CollectionRecord>, CustomErrors> {
+        self.read_collection_records().map_err(|e| {
+            CustomErrors::Query(
+                "Something went wrong while reading the collections to sync".to_owned(),
+                e,
+            )
+        })
+    }
+
+    fn read_collection_records(&self) -> rusqlite::Result<Vec<CollectionRecord>> {
+        let mut records: Vec<CollectionRecord> = vec![];
+        let mut stmt = self.conn.prepare(
+            "SELECT collections.name, collection_links.link
+            FROM collections
+            LEFT JOIN collection_links ON collection_links.collection = collections.name
+            ORDER BY collections.name, collection_links.position",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+        for row in rows {
+            let (name, link) = row?;
+            if records.last().map(|record| &record.name) != Some(&name) {
+                records.push(CollectionRecord {
+                    name,
+                    links: vec![],
+                });
+            }
+            if let (Some(record), Some(link)) = (records.last_mut(), link) {
+                record.links.push(link);
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// merge every link, tag, history entry and collection of another abhyas db file
+    /// into this one, the other file is opened read-only and left untouched
+    pub fn merge_database(
+        &self,
+        other: &Path,
+        policy: MergePolicy,
+        dry_run: bool,
+    ) -> Result<SyncSummary, CustomErrors> {
+        if !other.is_file() {
+            return Err(CustomErrors::InvalidInput(format!(
+                "The db file {} does not exist",
+                other.display()
+            )));
+        }
+        if self
+            .conn
+            .path()
+            .map(Path::new)
+            .and_then(|path| path.canonicalize().ok())
+            == other.canonicalize().ok()
+        {
+            return Err(CustomErrors::InvalidInput(
+                "Can't merge the db into itself".to_owned(),
+            ));
+        }
+
+        // the other file is only read, an in-memory copy gets the upgrades of older tables
+        let copy = Db::from_connection(copy_read_only(other)?)?;
+        let links = copy.get_link_records()?;
+        let collections = copy.get_collection_records()?;
+
+        self.merge_records(&links, &collections, policy, dry_run)
+    }
+
+    /// merge links and collections from another machine into the db
+    ///
+    /// missing links are added, links on both sides get the union of their tags and
+    /// history and their counters reconciled by the policy, collections get the
+    /// missing links appended in order
+    pub fn merge_records(
+        &self,
+        links: &[LinkRecord],
+        collections: &[CollectionRecord],
+        policy: MergePolicy,
+        dry_run: bool,
+    ) -> Result<SyncSummary, CustomErrors> {
+        let local: HashMap<String, LinkRecord> = self
+            .get_link_records()?
+            .into_iter()
+            .map(|record| (record.link.clone(), record))
+            .collect();
+
+        let tx = match self.conn.unchecked_transaction() {
+            Ok(val) => val,
+            Err(e) => return Err(CustomErrors::StatementFailed(e)),
+        };
+
+        let summary = match merge_records_in(&tx, &local, links, collections, policy) {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while merging the synced links".to_owned(),
+                    e,
+                ))
+            }
+        };
+
+        // dropping the transaction rolls the dry run back
+        if !dry_run {
+            if let Err(e) = tx.commit() {
+                return Err(CustomErrors::Query(
+                    "Something went wrong while merging the synced links".to_owned(),
+                    e,
+                ));
+            }
+            self.run_hook(
+                HookEvent::Import,
+                self.hook_links(HookEvent::Import, &summary.links),
+            );
+        }
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a db with the pending link `a`, `b` completed and tagged `dp`, `c` skipped
+    /// and `d` completed
+    fn sample_db() -> Result<Db, CustomErrors> {
+        let db = Db::open_in_memory()?;
+        for link in ["a", "b", "c", "d"] {
+            db.add_link(link.to_owned())?;
+        }
+        db.mark_as_complete("b")?;
+        db.mark_as_complete("d")?;
+        db.skip_link("c")?;
+        db.apply_bulk_action(&BulkAction::Tag("dp".to_owned()), &["b".to_owned()])?;
+        Ok(db)
+    }
+
+    fn links(links: Vec<Link>) -> Vec<String> {
+        links.into_iter().map(|link| link.link).collect()
+    }
+
+    #[test]
+    fn queries_links_by_state_and_tag() -> Result<(), CustomErrors> {
+        let db = sample_db()?;
+        let query = |filter: LinkFilter| db.query_links(&filter, LinkSort::Added, None, 0);
+
+        assert_eq!(links(query(LinkFilter::default())?), ["a", "b", "c", "d"]);
+        let completed = LinkFilter {
+            state: Some(LinkState::Completed),
+            ..LinkFilter::default()
+        };
+        assert_eq!(links(query(completed)?), ["b", "d"]);
+        let pending = LinkFilter {
+            state: Some(LinkState::Pending),
+            ..LinkFilter::default()
+        };
+        assert_eq!(links(query(pending)?), ["a"]);
+        let tagged = LinkFilter {
+            tag: Some("dp".to_owned()),
+            ..LinkFilter::default()
+        };
+        assert_eq!(query(tagged)?[0].tags, ["dp"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn pages_through_the_links() -> Result<(), CustomErrors> {
+        let db = sample_db()?;
+
+        let page = db.query_links(&LinkFilter::default(), LinkSort::Link, Some(2), 1)?;
+
+        assert_eq!(links(page), ["b", "c"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resets_only_the_filtered_links() -> Result<(), CustomErrors> {
+        let db = sample_db()?;
+        let filter = ResetFilter {
+            completed: true,
+            tag: Some("dp".to_owned()),
+            ..ResetFilter::default()
+        };
+
+        assert_eq!(db.get_links_to_reset(&filter)?, ["b"]);
+        assert_eq!(db.reset_links(&filter)?, ["b"]);
+        assert_eq!(
+            db.get_link("b")?.map(|link| link.state),
+            Some(LinkState::Pending)
+        );
+        assert_eq!(
+            db.get_link("d")?.map(|link| link.state),
+            Some(LinkState::Completed)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn resets_completed_and_skipped_links_without_a_state() -> Result<(), CustomErrors> {
+        let db = sample_db()?;
+
+        let reset = db.reset_links(&ResetFilter::default())?;
+
+        assert_eq!(reset, ["b", "c", "d"]);
+        let skipped = db.get_link("c")?.expect("the link exists");
+        assert_eq!(skipped.state, LinkState::Pending);
+        assert_eq!(skipped.skipped_until, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn counts_links_without_a_completion_date_as_completed_before() -> Result<(), CustomErrors> {
+        let db = sample_db()?;
+        // `b` was completed before completed_at was recorded, `d` after the cutoff
+        db.conn
+            .execute("UPDATE links SET completed_at = NULL WHERE link = 'b'", ())
+            .expect("the completion date is cleared");
+        let filter = ResetFilter {
+            completed: true,
+            completed_before: Some("2000-01-01".to_owned()),
+            ..ResetFilter::default()
+        };
+
+        assert_eq!(db.get_links_to_reset(&filter)?, ["b"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn resets_only_the_picked_links() -> Result<(), CustomErrors> {
+        let db = sample_db()?;
+        let filter = ResetFilter {
+            links: vec!["d".to_owned()],
+            ..ResetFilter::default()
+        };
+
+        assert_eq!(db.reset_links(&filter)?, ["d"]);
+        assert_eq!(
+            db.get_link("b")?.map(|link| link.state),
+            Some(LinkState::Completed)
+        );
+
+        Ok(())
     }
 }
@@ -0,0 +1,128 @@
+use rusqlite::ErrorCode;
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// process exit code when the link already exists
+pub const EXIT_DUPLICATE: i32 = 3;
+/// process exit code when another process holds a lock on the db
+pub const EXIT_DATABASE_LOCKED: i32 = 4;
+/// process exit code for any other db failure
+pub const EXIT_DATABASE: i32 = 5;
+/// process exit code when reading or writing a file fails
+pub const EXIT_IO: i32 = 6;
+/// process exit code for invalid arguments or input, same as clap's usage errors
+pub const EXIT_INVALID_INPUT: i32 = 2;
+/// process exit code when the user cancels or interrupts a prompt
+pub const EXIT_USER_QUIT: i32 = 130;
+/// process exit code for everything else
+pub const EXIT_OTHER: i32 = 1;
+
+/// errors returned by every fallible operation of abhyas, the underlying
+/// sqlite or io error is kept as the source
+#[derive(Debug)]
+pub enum CustomErrors {
+    /// the platform cache directory could not be found
+    CacheDirectoryNotFound,
+    /// the directory holding the db file could not be created
+    CreateDirectoryFailed(io::Error),
+    /// the db file could not be created
+    FileCreationFailed(io::Error),
+    /// the db file could not be opened
+    DBConnectionFailed(rusqlite::Error),
+    /// creating or upgrading the tables failed
+    DBQueryFailed(rusqlite::Error),
+    /// the link is already present in the db
+    DuplicateLinkValue,
+    /// the link is not present in the db
+    LinkNotFound(String),
+    /// a sql statement could not be prepared
+    StatementFailed(rusqlite::Error),
+    /// a query referenced a column that does not exist
+    InvalidColumnName(String),
+    /// a query failed, along with what was being done at the time
+    Query(String, rusqlite::Error),
+    /// reading or writing a file failed, along with what was being done at the time
+    Io(String, io::Error),
+    /// the arguments or the user input could not be understood
+    InvalidInput(String),
+    /// the user pressed escape in a prompt
+    OperationCanceled,
+    /// the user pressed ctrl-c in a prompt
+    OperationInterrupted,
+    /// any other failure along with what was being done at the time
+    Context(String, Box<dyn Error + Send + Sync>),
+    /// any other failure without an underlying cause
+    Others(String),
+    /// the user chose to quit the application
+    Exit,
+}
+
+impl CustomErrors {
+    /// the process exit code for this kind of error, `Exit` is a regular quit and maps to 0
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CustomErrors::Exit => 0,
+            CustomErrors::DuplicateLinkValue => EXIT_DUPLICATE,
+            CustomErrors::DBConnectionFailed(e)
+            | CustomErrors::DBQueryFailed(e)
+            | CustomErrors::StatementFailed(e)
+            | CustomErrors::Query(_, e) => match e.sqlite_error_code() {
+                Some(ErrorCode::DatabaseBusy) | Some(ErrorCode::DatabaseLocked) => {
+                    EXIT_DATABASE_LOCKED
+                }
+                _ => EXIT_DATABASE,
+            },
+            CustomErrors::InvalidColumnName(_) => EXIT_DATABASE,
+            CustomErrors::CacheDirectoryNotFound
+            | CustomErrors::CreateDirectoryFailed(_)
+            | CustomErrors::FileCreationFailed(_)
+            | CustomErrors::Io(_, _) => EXIT_IO,
+            CustomErrors::InvalidInput(_) | CustomErrors::LinkNotFound(_) => EXIT_INVALID_INPUT,
+            CustomErrors::OperationCanceled | CustomErrors::OperationInterrupted => EXIT_USER_QUIT,
+            CustomErrors::Context(_, _) | CustomErrors::Others(_) => EXIT_OTHER,
+        }
+    }
+}
+
+impl fmt::Display for CustomErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CustomErrors::CacheDirectoryNotFound => write!(f, "The cache directory was not found"),
+            CustomErrors::CreateDirectoryFailed(_) => write!(f, "Couldn't create the db directory"),
+            CustomErrors::FileCreationFailed(_) => write!(f, "Couldn't create the db file"),
+            CustomErrors::DBConnectionFailed(_) => write!(f, "DB connection failed"),
+            CustomErrors::DBQueryFailed(_) => write!(f, "DB query failed"),
+            CustomErrors::DuplicateLinkValue => write!(f, "Link already exists, input other link"),
+            CustomErrors::LinkNotFound(link) => write!(f, "The link {} does not exist", link),
+            CustomErrors::StatementFailed(_) => write!(f, "Failed to execute the statement"),
+            CustomErrors::InvalidColumnName(column_name) => {
+                write!(f, "column {} does not exist", column_name)
+            }
+            CustomErrors::OperationCanceled => write!(f, "User cancelled the operation"),
+            CustomErrors::OperationInterrupted => write!(f, "User forcefully quit the operation"),
+            CustomErrors::Query(msg, _)
+            | CustomErrors::Io(msg, _)
+            | CustomErrors::Context(msg, _)
+            | CustomErrors::InvalidInput(msg)
+            | CustomErrors::Others(msg) => write!(f, "{}", msg),
+            CustomErrors::Exit => write!(f, "You've successfully quit the application :)"),
+        }
+    }
+}
+
+impl Error for CustomErrors {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CustomErrors::CreateDirectoryFailed(e)
+            | CustomErrors::FileCreationFailed(e)
+            | CustomErrors::Io(_, e) => Some(e),
+            CustomErrors::DBConnectionFailed(e)
+            | CustomErrors::DBQueryFailed(e)
+            | CustomErrors::StatementFailed(e)
+            | CustomErrors::Query(_, e) => Some(e),
+            CustomErrors::Context(_, e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
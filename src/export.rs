@@ -0,0 +1,296 @@
+use crate::database::Db;
+use crate::link::{Link, LinkFilter, LinkSort};
+use crate::CustomErrors;
+use std::fs;
+use std::path::Path;
+
+/// longest line of an ics file in bytes, longer lines are folded
+const ICS_LINE_LIMIT: usize = 75;
+
+/// returns every link on its own line, the same format read by `read_links_from_file`
+pub fn export_links(db: &Db) -> Result<String, CustomErrors> {
+    let links = db.get_links()?;
+
+    let mut output = links.join("\n");
+    if !output.is_empty() {
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// write every link to the given file, one link per line
+pub fn write_links_to_file(db: &Db, file: &Path) -> Result<usize, CustomErrors> {
+    let output = export_links(db)?;
+
+    match fs::write(file, &output) {
+        Ok(_) => Ok(output.lines().count()),
+        Err(e) => Err(CustomErrors::Io(
+            format!("Couldn't write the links to {}", file.display()),
+            e,
+        )),
+    }
+}
+
+/// returns the links as an Anki text file with one basic card per link, the
+/// title and url on the front and the notes on the back, tags become Anki tags
+///
+/// the header lines tell Anki the separator, note type, deck and tag column,
+/// so the file imports through File > Import without any options
+pub fn export_anki_tsv(db: &Db) -> Result<String, CustomErrors> {
+    let links = db.query_links(&LinkFilter::default(), LinkSort::Added, None, 0)?;
+    Ok(anki_tsv(&links))
+}
+
+/// write the Anki text file to the given file, returns the number of cards
+pub fn write_anki_tsv_to_file(db: &Db, file: &Path) -> Result<usize, CustomErrors> {
+    let links = db.query_links(&LinkFilter::default(), LinkSort::Added, None, 0)?;
+
+    match fs::write(file, anki_tsv(&links)) {
+        Ok(_) => Ok(links.len()),
+        Err(e) => Err(CustomErrors::Io(
+            format!("Couldn't write the Anki cards to {}", file.display()),
+            e,
+        )),
+    }
+}
+
+fn anki_tsv(links: &[Link]) -> String {
+    let mut output =
+        String::from("#separator:tab\n#html:true\n#notetype:Basic\n#deck:abhyas\n#tags column:3\n");
+    for link in links {
+        output.push_str(&anki_card(link).join("\t"));
+        output.push('\n');
+    }
+
+    output
+}
+
+/// front, back and tags of the card
+fn anki_card(link: &Link) -> [String; 3] {
+    let url = escape_html(&link.link);
+    let front = if link.title.is_empty() {
+        format!("<a href=\"{0}\">{0}</a>", url)
+    } else {
+        // anki reads a line starting with # as a comment, the entity still shows a #
+        let title = escape_html(&link.title);
+        let title = match title.strip_prefix('#') {
+            Some(rest) => format!("&#35;{}", rest),
+            None => title,
+        };
+        format!("{}<br><a href=\"{}\">{}</a>", title, url, url)
+    };
+
+    let mut back = escape_html(&link.notes).replace('\n', "<br>");
+    if !link.tags.is_empty() {
+        if !back.is_empty() {
+            back.push_str("<br><br>");
+        }
+        back.push_str(&format!("Tags: {}", escape_html(&link.tags.join(", "))));
+    }
+
+    // anki tags are separated by spaces
+    let tags = link
+        .tags
+        .iter()
+        .map(|tag| tag.split_whitespace().collect::<Vec<_>>().join("_"))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    [front, back, tags]
+}
+
+/// escape html and replace the tabs and newlines that would break the row apart
+fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\t', " ")
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+}
+
+/// returns an iCalendar file with an all-day event on the review date of every
+/// completed link, `review_after_days` after its last completion
+///
+/// the output only depends on the db, exporting twice without practicing in
+/// between gives the same file, and every link keeps the same event uid so
+/// calendars update the event instead of adding a new one
+pub fn export_ics(db: &Db, review_after_days: u32) -> Result<String, CustomErrors> {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        format!("PRODID:-//abhyas//abhyas {}//EN", env!("CARGO_PKG_VERSION")),
+        "CALSCALE:GREGORIAN".to_owned(),
+        "X-WR-CALNAME:abhyas reviews".to_owned(),
+    ];
+
+    for (link, due) in db.get_review_dates(review_after_days)? {
+        lines.extend(review_event(&link, &due));
+    }
+    lines.push("END:VCALENDAR".to_owned());
+
+    let mut output = String::new();
+    for line in lines {
+        output.push_str(&fold_ics_line(&line));
+        output.push_str("\r\n");
+    }
+
+    Ok(output)
+}
+
+/// write the review calendar to the given file, returns the number of events
+pub fn write_ics_to_file(
+    db: &Db,
+    review_after_days: u32,
+    file: &Path,
+) -> Result<usize, CustomErrors> {
+    let output = export_ics(db, review_after_days)?;
+
+    match fs::write(file, &output) {
+        Ok(_) => Ok(output.matches("BEGIN:VEVENT").count()),
+        Err(e) => Err(CustomErrors::Io(
+            format!("Couldn't write the calendar to {}", file.display()),
+            e,
+        )),
+    }
+}
+
+fn review_event(link: &Link, due: &str) -> Vec<String> {
+    let name = if link.title.is_empty() {
+        &link.link
+    } else {
+        &link.title
+    };
+    // the last completion is when the event last changed
+    let stamp = link
+        .completed_at
+        .as_deref()
+        .unwrap_or_default()
+        .replace(['-', ':'], "")
+        .replace(' ', "T");
+
+    let mut description = format!("{}\nSolved {} times", link.link, link.solved_count);
+    if !link.tags.is_empty() {
+        description.push_str(&format!("\nTags: {}", link.tags.join(", ")));
+    }
+
+    let mut event = vec![
+        "BEGIN:VEVENT".to_owned(),
+        format!("UID:review-{:016x}@abhyas", fnv1a(&link.link)),
+        format!("DTSTAMP:{}Z", stamp),
+        format!("DTSTART;VALUE=DATE:{}", due.replace('-', "")),
+        format!("SUMMARY:{}", escape_ics_text(&format!("Review {}", name))),
+        format!("DESCRIPTION:{}", escape_ics_text(&description)),
+    ];
+    if link.link.starts_with("http://") || link.link.starts_with("https://") {
+        event.push(format!("URL:{}", link.link));
+    }
+    event.push("END:VEVENT".to_owned());
+
+    event
+}
+
+/// escape the characters with a meaning in ics text values
+fn escape_ics_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// split lines longer than the limit, continuation lines start with a space
+fn fold_ics_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > ICS_LINE_LIMIT {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+
+    folded
+}
+
+/// stable 64 bit hash of the link, std's hasher may change between releases
+fn fnv1a(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::process;
+
+    #[test]
+    fn keeps_cards_with_a_hash_title() -> Result<(), CustomErrors> {
+        let db = Db::open_in_memory()?;
+        db.add_link("https://a.com/1".to_owned())?;
+        db.add_link("https://a.com/2".to_owned())?;
+        db.update_link_details("https://a.com/1", "#1 Two Sum", "")?;
+
+        let file = env::temp_dir().join(format!("abhyas-anki-{}.txt", process::id()));
+        let count = write_anki_tsv_to_file(&db, &file)?;
+        let output = fs::read_to_string(&file).unwrap_or_default();
+        let _ = fs::remove_file(&file);
+
+        assert_eq!(count, 2);
+        let cards: Vec<&str> = output
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .collect();
+        assert_eq!(cards.len(), 2);
+        assert!(cards[0].starts_with("&#35;1 Two Sum<br>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn escapes_ics_text() {
+        assert_eq!(escape_ics_text("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+    }
+
+    #[test]
+    fn folds_long_ics_lines() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold_ics_line(&line);
+
+        assert!(folded
+            .split("\r\n")
+            .all(|part| part.len() <= ICS_LINE_LIMIT));
+        assert!(folded
+            .split("\r\n")
+            .skip(1)
+            .all(|part| part.starts_with(' ')));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+        assert_eq!(fold_ics_line("SUMMARY:short"), "SUMMARY:short");
+    }
+
+    #[test]
+    fn exports_a_review_per_completed_link() -> Result<(), CustomErrors> {
+        let db = Db::open_in_memory()?;
+        db.add_link("https://a.com/1".to_owned())?;
+        db.add_link("https://a.com/2".to_owned())?;
+        db.update_link_details("https://a.com/1", "Two Sum, Easy", "")?;
+        db.mark_as_complete("https://a.com/1")?;
+
+        let output = export_ics(&db, 7)?;
+
+        assert_eq!(output.matches("BEGIN:VEVENT").count(), 1);
+        assert!(output.contains("SUMMARY:Review Two Sum\\, Easy\r\n"));
+        assert!(output.lines().all(|line| line.len() <= ICS_LINE_LIMIT + 1));
+        assert_eq!(export_ics(&db, 7)?, output);
+
+        Ok(())
+    }
+}
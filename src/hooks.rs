@@ -0,0 +1,220 @@
+use crate::link::Link;
+use crate::CustomErrors;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// how often a running hook is checked for completion
+const HOOK_POLL: Duration = Duration::from_millis(50);
+
+/// how long the stderr of a finished hook is waited for, a process the hook left
+/// running in the background may keep it open
+const STDERR_WAIT: Duration = Duration::from_millis(200);
+
+/// a change to links that can run its own hook command
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookEvent {
+    Add,
+    Complete,
+    Skip,
+    Delete,
+    /// links added or updated from a file, a sync directory or another db
+    Import,
+}
+
+impl fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HookEvent::Add => write!(f, "on_add"),
+            HookEvent::Complete => write!(f, "on_complete"),
+            HookEvent::Skip => write!(f, "on_skip"),
+            HookEvent::Delete => write!(f, "on_delete"),
+            HookEvent::Import => write!(f, "on_import"),
+        }
+    }
+}
+
+/// json written to the stdin of a hook
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    event: HookEvent,
+    links: &'a [Link],
+}
+
+/// commands run after links change, the `[hooks]` table of the config,
+/// every command is a program followed by its arguments
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    /// a hook still running after this many seconds is killed
+    pub timeout_secs: u64,
+    pub on_add: Vec<String>,
+    pub on_complete: Vec<String>,
+    pub on_skip: Vec<String>,
+    pub on_delete: Vec<String>,
+    pub on_import: Vec<String>,
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Hooks {
+            timeout_secs: 10,
+            on_add: vec![],
+            on_complete: vec![],
+            on_skip: vec![],
+            on_delete: vec![],
+            on_import: vec![],
+        }
+    }
+}
+
+impl Hooks {
+    fn command(&self, event: HookEvent) -> &[String] {
+        match event {
+            HookEvent::Add => &self.on_add,
+            HookEvent::Complete => &self.on_complete,
+            HookEvent::Skip => &self.on_skip,
+            HookEvent::Delete => &self.on_delete,
+            HookEvent::Import => &self.on_import,
+        }
+    }
+
+    /// returns true when a command is configured for the event
+    pub fn is_set(&self, event: HookEvent) -> bool {
+        !self.command(event).is_empty()
+    }
+
+    /// run the command of the event with `{"event": ..., "links": [...]}` on stdin,
+    /// waiting at most `timeout_secs` for it to exit, does nothing without a command
+    pub fn run(&self, event: HookEvent, links: &[Link]) -> Result<(), CustomErrors> {
+        let (program, args) = match self.command(event).split_first() {
+            Some(val) => val,
+            None => return Ok(()),
+        };
+
+        let payload = match serde_json::to_string(&HookPayload { event, links }) {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(CustomErrors::Context(
+                    format!("Couldn't serialize the payload of the {} hook", event),
+                    Box::new(e),
+                ))
+            }
+        };
+
+        // stdout stays clean for --json output and stderr would draw over the terminal
+        // interface, the errors of the hook end up in the error it returns
+        let mut child = match Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+        {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(CustomErrors::Io(
+                    format!("Couldn't run the {} hook {}", event, program),
+                    e,
+                ))
+            }
+        };
+
+        // a hook that never reads its stdin must not block the timeout
+        if let Some(mut stdin) = child.stdin.take() {
+            thread::spawn(move || {
+                let _ = stdin.write_all(payload.as_bytes());
+            });
+        }
+        let stderr = read_in_background(child.stderr.take());
+
+        let deadline = Instant::now() + Duration::from_secs(self.timeout_secs);
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => return Ok(()),
+                Ok(Some(status)) => {
+                    return Err(CustomErrors::Others(format!(
+                        "The {} hook {} failed with {}{}",
+                        event,
+                        program,
+                        status,
+                        hook_output(&stderr)
+                    )))
+                }
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(CustomErrors::Others(format!(
+                        "The {} hook {} was stopped after {} seconds{}",
+                        event,
+                        program,
+                        self.timeout_secs,
+                        hook_output(&stderr)
+                    )));
+                }
+                Ok(None) => thread::sleep(HOOK_POLL),
+                Err(e) => {
+                    return Err(CustomErrors::Io(
+                        format!("Couldn't wait for the {} hook {}", event, program),
+                        e,
+                    ))
+                }
+            };
+        }
+    }
+}
+
+/// read the pipe to the end on its own thread so a hook writing a lot doesn't block on it
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    if let Some(mut pipe) = pipe {
+        thread::spawn(move || {
+            let mut output = String::new();
+            let _ = pipe.read_to_string(&mut output);
+            let _ = sender.send(output);
+        });
+    }
+    receiver
+}
+
+/// the stderr of a hook to append to its error, empty when it wrote nothing
+fn hook_output(stderr: &Receiver<String>) -> String {
+    match stderr.recv_timeout(STDERR_WAIT) {
+        Ok(output) if !output.trim().is_empty() => format!(": {}", output.trim()),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hooks(script: &str) -> Hooks {
+        Hooks {
+            on_add: vec!["sh".to_owned(), "-c".to_owned(), script.to_owned()],
+            ..Hooks::default()
+        }
+    }
+
+    #[test]
+    fn keeps_the_stderr_of_a_failed_hook() {
+        let result = hooks("cat > /dev/null; echo 'no token' >&2; exit 3").run(HookEvent::Add, &[]);
+
+        match result {
+            Err(CustomErrors::Others(e)) => assert!(e.ends_with(": no token"), "{}", e),
+            _ => panic!("the hook should fail"),
+        };
+    }
+
+    #[test]
+    fn ignores_the_stderr_of_a_successful_hook() {
+        assert!(hooks("echo 'just saying' >&2")
+            .run(HookEvent::Add, &[])
+            .is_ok());
+    }
+}
@@ -0,0 +1,25 @@
+use crate::CustomErrors;
+use std::fs;
+use std::path::Path;
+
+/// read links from a file with one link per line, blank lines are ignored
+pub fn read_links_from_file(file: &Path) -> Result<Vec<String>, CustomErrors> {
+    let data = match fs::read_to_string(file) {
+        Ok(val) => val,
+        Err(e) => {
+            return Err(CustomErrors::Io(
+                format!("Couldn't read the links from {}", file.display()),
+                e,
+            ))
+        }
+    };
+
+    let links: Vec<String> = data
+        .lines()
+        .map(|link| link.trim())
+        .filter(|link| !link.is_empty())
+        .map(|link| link.to_owned())
+        .collect();
+
+    Ok(links)
+}
@@ -0,0 +1,52 @@
+//! Abhyas keeps a database of links to practice, e.g. coding problems, and
+//! tracks which of them were completed or skipped.
+//!
+//! The `abhyas` binary is a thin interactive layer on top of this library, so
+//! other tools can embed the same database and logic:
+//!
+//! ```no_run
+//! use abhyas::{read_links_from_file, Db};
+//! use std::path::Path;
+//!
+//! # fn main() -> Result<(), abhyas::CustomErrors> {
+//! let db = Db::open_default()?;
+//! let links = read_links_from_file(Path::new("links.txt"))?;
+//! let inserted = db.insert_links_from_file(&links)?;
+//!
+//! let status = db.get_status_summary()?;
+//! println!(
+//!     "{inserted} new, {} total, {} completed, {} pending",
+//!     status.total, status.completed, status.pending
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod config;
+pub mod database;
+pub mod error;
+pub mod export;
+pub mod hooks;
+pub mod import;
+pub mod link;
+mod schema;
+pub mod search;
+pub mod session;
+pub mod stats;
+pub mod sync;
+pub mod webhook;
+
+pub use config::Config;
+pub use database::{link_platform, BulkAction, Db, ResetFilter};
+pub use error::CustomErrors;
+pub use export::{
+    export_anki_tsv, export_ics, export_links, write_anki_tsv_to_file, write_ics_to_file,
+    write_links_to_file,
+};
+pub use hooks::{HookEvent, Hooks};
+pub use import::read_links_from_file;
+pub use link::{Link, LinkFilter, LinkSort, LinkState, SelectionStrategy};
+pub use search::SearchQuery;
+pub use session::{Session, SessionLink, SessionOutcome};
+pub use stats::{Stats, Status};
+pub use webhook::{Webhook, WebhookEvent, Webhooks};
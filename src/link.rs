@@ -0,0 +1,116 @@
+use serde::Serialize;
+use std::fmt;
+
+/// where a link currently stands in the practice pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkState {
+    /// neither completed, skipped nor failed, served by "Get Link"
+    Pending,
+    /// solved at least once since the last reset
+    Completed,
+    /// set aside until the skipped links are reset, or until the snooze runs out
+    Skipped,
+    /// attempted without success since the last solve, served before pending links
+    Failed,
+}
+
+impl LinkState {
+    /// skipping takes precedence because a skipped link is out of the pool either way
+    pub fn from_flags(is_solved: bool, is_skipped: bool, is_failed: bool) -> Self {
+        if is_skipped {
+            LinkState::Skipped
+        } else if is_solved {
+            LinkState::Completed
+        } else if is_failed {
+            LinkState::Failed
+        } else {
+            LinkState::Pending
+        }
+    }
+}
+
+impl fmt::Display for LinkState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinkState::Pending => write!(f, "pending"),
+            LinkState::Completed => write!(f, "completed"),
+            LinkState::Skipped => write!(f, "skipped"),
+            LinkState::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// everything stored about a single link, serialized as is by the json output
+#[derive(Clone, Debug, Serialize)]
+pub struct Link {
+    pub link: String,
+    pub title: String,
+    pub notes: String,
+    pub solved_count: i32,
+    /// attempts marked as "Couldn't Solve"
+    pub failed_count: i32,
+    pub state: LinkState,
+    /// utc time of the last completion as `YYYY-MM-DD HH:MM:SS`
+    pub completed_at: Option<String>,
+    /// utc time when a snoozed link goes back to the pool as `YYYY-MM-DD HH:MM:SS`,
+    /// None for links that aren't skipped or are skipped until a reset
+    pub skipped_until: Option<String>,
+    pub tags: Vec<String>,
+}
+
+/// narrows down the links returned by `Db::query_links`, empty fields match everything
+#[derive(Default)]
+pub struct LinkFilter {
+    /// pending links are neither completed, skipped nor failed, completed and
+    /// skipped match the respective flag and failed links are neither completed nor skipped
+    pub state: Option<LinkState>,
+    pub tag: Option<String>,
+    pub collection: Option<String>,
+}
+
+/// order of the links returned by `Db::query_links`
+#[derive(Clone, Copy, Default)]
+pub enum LinkSort {
+    /// the order in which the links were added, or the order of the collection
+    /// when filtering by one
+    #[default]
+    Added,
+    /// alphabetical by link
+    Link,
+    /// most solved first
+    SolvedCount,
+    /// most recently completed first
+    CompletedAt,
+    /// longest ago completed first, never completed links before all others
+    LeastRecentlyCompleted,
+    /// fewest solves first, then most failed attempts, ties in random order
+    LeastSolved,
+    /// a different random order on every call
+    Random,
+}
+
+/// how the links of a practice session are picked
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    /// failed and then pending links in the order they were added, the same as "Get Link"
+    #[default]
+    Next,
+    /// failed and pending links in random order
+    Random,
+    /// links that aren't skipped, fewest solves and then most failures first
+    LeastSolved,
+    /// completed links, the longest ago completed first
+    Review,
+}
+
+impl fmt::Display for SelectionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SelectionStrategy::Next => write!(f, "next"),
+            SelectionStrategy::Random => write!(f, "random"),
+            SelectionStrategy::LeastSolved => write!(f, "least-solved"),
+            SelectionStrategy::Review => write!(f, "review"),
+        }
+    }
+}
@@ -1,3 +1,10 @@
+use crate::timer::format_duration;
+use abhyas::link::Link;
+use abhyas::session::{Session, SessionLink, SessionOutcome};
+use abhyas::stats::{self, SolveTime};
+use abhyas::CustomErrors;
+use serde::Serialize;
+use std::io::{self, ErrorKind, Write};
 use tabled::{
     settings::{object::Segment, Alignment, Modify, Style},
     Table, Tabled,
@@ -7,7 +14,9 @@ use tabled::{
 struct Links {
     id: usize,
     link: String,
+    state: String,
     solved_count: i32,
+    failed_count: i32,
 }
 
 #[derive(Tabled)]
@@ -15,13 +24,54 @@ struct Status {
     total_links: i32,
     completed_links: i32,
     skipped_links: i32,
+    failed_links: i32,
 }
 
-pub fn pretty_status(total_links: i32, completed_links: i32, skipped_links: i32) {
+#[derive(Tabled)]
+struct SolveTimes {
+    name: String,
+    attempts: i32,
+    average: String,
+    best: String,
+}
+
+#[derive(Tabled)]
+struct SessionLinks {
+    id: usize,
+    link: String,
+    outcome: String,
+    time: String,
+}
+
+#[derive(Tabled)]
+struct Sessions {
+    id: i64,
+    started_at: String,
+    strategy: String,
+    tag: String,
+    solved: String,
+    time: String,
+}
+
+#[derive(Tabled)]
+struct CollectionStatus {
+    collection: String,
+    total_links: i32,
+    completed_links: i32,
+    skipped_links: i32,
+}
+
+pub fn pretty_status(
+    total_links: i32,
+    completed_links: i32,
+    skipped_links: i32,
+    failed_links: i32,
+) {
     let data = vec![Status {
         total_links,
         completed_links,
         skipped_links,
+        failed_links,
     }];
 
     let mut table = Table::new(data);
@@ -37,14 +87,105 @@ pub fn pretty_status(total_links: i32, completed_links: i32, skipped_links: i32)
     println!("{}", table_string);
 }
 
-pub fn pretty_print(data: &[(String, i32)]) {
+pub fn pretty_collection_status(data: &[stats::CollectionStatus]) {
+    let new_data: Vec<CollectionStatus> = data
+        .iter()
+        .map(|status| CollectionStatus {
+            collection: status.name.clone(),
+            total_links: status.total,
+            completed_links: status.completed,
+            skipped_links: status.skipped,
+        })
+        .collect();
+
+    let mut table = Table::new(new_data);
+    table.with(
+        Modify::new(Segment::all())
+            .with(Alignment::center())
+            .with(Alignment::top()),
+    );
+
+    let table = table.with(Style::modern());
+    let table_string = table.to_string();
+
+    println!("{}", table_string);
+}
+
+pub fn pretty_solve_times(data: &[SolveTime]) {
+    let new_data: Vec<SolveTimes> = data
+        .iter()
+        .map(|times| SolveTimes {
+            name: times.name.to_string(),
+            attempts: times.attempts,
+            average: format_duration(times.average_secs),
+            best: format_duration(times.best_secs),
+        })
+        .collect();
+
+    let mut table = Table::new(new_data);
+    let table = table.with(Style::modern());
+    let table_string = table.to_string();
+
+    println!("{}", table_string);
+}
+
+pub fn pretty_session_links(data: &[SessionLink]) {
+    let new_data: Vec<SessionLinks> = data
+        .iter()
+        .enumerate()
+        .map(|(id, entry)| SessionLinks {
+            id: id + 1,
+            link: entry.link.to_string(),
+            outcome: entry.outcome.to_string(),
+            time: format_duration(entry.duration_secs),
+        })
+        .collect();
+
+    let mut table = Table::new(new_data);
+    let table = table.with(Style::modern());
+    let table_string = table.to_string();
+
+    println!("{}", table_string);
+}
+
+pub fn pretty_sessions(data: &[Session]) {
+    let new_data: Vec<Sessions> = data
+        .iter()
+        .map(|session| Sessions {
+            id: session.id,
+            started_at: session.started_at.to_string(),
+            strategy: session.strategy.to_string(),
+            tag: session.tag.clone().unwrap_or_default(),
+            solved: format!(
+                "{}/{}",
+                session.count(SessionOutcome::Solved),
+                session.links.len()
+            ),
+            time: format!(
+                "{}/{}",
+                format_duration(session.total_secs()),
+                format_duration(session.minutes as i64 * 60)
+            ),
+        })
+        .collect();
+
+    let mut table = Table::new(new_data);
+    let table = table.with(Style::modern());
+    let table_string = table.to_string();
+
+    println!("{}", table_string);
+}
+
+pub fn pretty_print(data: &[Link]) {
     let new_data: Vec<Links> = data
         .iter()
         .enumerate()
-        .map(|(id, (link, solved_count))| Links {
+        .map(|(id, link)| Links {
             id: id + 1,
-            link: link.to_string(),
-            solved_count: *solved_count,
+            link: link.link.to_string(),
+            state: link.state.to_string(),
+            solved_count: link.solved_count,
+            failed_count: link.failed_count,
         })
         .collect();
 
@@ -54,3 +195,36 @@ pub fn pretty_print(data: &[(String, i32)]) {
 
     println!("{}", table_string);
 }
+
+/// shown when no link is pending, as text or as the json error of `abhyas next --json`
+/// and `GET /links/next`
+pub const NO_PENDING_LINKS: &str = "No pending links left";
+
+/// an error in json output, `{"error": "..."}`
+#[derive(Serialize)]
+pub struct JsonError {
+    pub error: String,
+}
+
+/// print the value as pretty json, the same shapes are returned by `abhyas serve`,
+/// a reader that stops early, e.g. `| head`, isn't an error
+pub fn print_json<T: Serialize>(value: &T) -> Result<(), CustomErrors> {
+    let json = match serde_json::to_string_pretty(value) {
+        Ok(val) => val,
+        Err(e) => {
+            return Err(CustomErrors::Context(
+                "Something went wrong while writing the json output".to_owned(),
+                Box::new(e),
+            ))
+        }
+    };
+
+    match writeln!(io::stdout().lock(), "{}", json) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => Ok(()),
+        Err(e) => Err(CustomErrors::Io(
+            "Couldn't write the json output".to_owned(),
+            e,
+        )),
+    }
+}
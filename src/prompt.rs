@@ -0,0 +1,25 @@
+use abhyas::CustomErrors;
+use inquire::error::{InquireError, InquireResult};
+use inquire::Confirm;
+
+/// shared handling for every interactive prompt, pass the result of `prompt_skippable()`
+///
+/// escape returns `Ok(None)` so the caller can go back one level, ctrl-c turns into
+/// `CustomErrors::OperationInterrupted` which quits the application cleanly
+pub fn ask<T>(result: InquireResult<Option<T>>, context: &str) -> Result<Option<T>, CustomErrors> {
+    match result {
+        Ok(val) => Ok(val),
+        Err(InquireError::OperationCanceled) => Ok(None),
+        Err(InquireError::OperationInterrupted) => Err(CustomErrors::OperationInterrupted),
+        Err(e) => Err(CustomErrors::Context(context.to_owned(), Box::new(e))),
+    }
+}
+
+/// ask a yes/no question defaulting to no, escape counts as no
+pub fn confirm(message: &str) -> Result<bool, CustomErrors> {
+    let confirmed = ask(
+        Confirm::new(message).with_default(false).prompt_skippable(),
+        "Something went wrong while asking for confirmation",
+    )?;
+    Ok(confirmed.unwrap_or(false))
+}
@@ -0,0 +1,188 @@
+use crate::args::RemindArgs;
+use crate::utility::{show_error, show_green};
+use abhyas::config::Config;
+use abhyas::database::Db;
+use abhyas::CustomErrors;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// check the daily goal and due reviews once, or every few minutes with --every
+pub fn remind(db: &Db, args: RemindArgs) -> Result<(), CustomErrors> {
+    let minutes = match args.every {
+        Some(val) => val,
+        None => return remind_once(db, args.dry_run),
+    };
+
+    show_green(
+        format!(
+            "Checking for outstanding practice every {} minutes",
+            minutes
+        )
+        .as_str(),
+    );
+    loop {
+        // a failing check or notify command shouldn't stop the daemon
+        if let Err(e) = remind_once(db, args.dry_run) {
+            show_error(&format!("Error: {}", e));
+        }
+        thread::sleep(Duration::from_secs(minutes * 60));
+    }
+}
+
+/// the config is read on every check so a running daemon picks up changes
+fn remind_once(db: &Db, dry_run: bool) -> Result<(), CustomErrors> {
+    let config = Config::load_default()?;
+
+    let message = match reminder_message(db, &config)? {
+        Some(val) => val,
+        None => {
+            show_green("Nothing to practice, today's goal is met and no reviews are due");
+            return Ok(());
+        }
+    };
+
+    if dry_run {
+        println!("{}", message);
+        return Ok(());
+    }
+
+    notify(&config, &message)?;
+    show_green(format!("Sent the reminder: {}", message).as_str());
+
+    Ok(())
+}
+
+/// returns the reminder text, or None when today's goal is met and no reviews are due
+fn reminder_message(db: &Db, config: &Config) -> Result<Option<String>, CustomErrors> {
+    let completed = db.get_completed_today()?;
+    let goal = config.daily_goal as i32;
+    let due = db.get_due_reviews(config.review_after_days)?;
+
+    let mut parts = vec![];
+    if completed < goal {
+        parts.push(format!("{} of {} links done today", completed, goal));
+    }
+    if !due.is_empty() {
+        parts.push(format!("{} reviews due", due.len()));
+    }
+    if parts.is_empty() {
+        return Ok(None);
+    }
+
+    // new links count towards the goal, reviews come after
+    let next = if completed < goal {
+        db.get_single_link()?.or_else(|| due.first().cloned())
+    } else {
+        due.first().cloned()
+    };
+
+    let mut message = parts.join(", ");
+    if let Some(link) = next {
+        message.push_str(&format!(", next: {}", link.link));
+    }
+
+    Ok(Some(message))
+}
+
+/// run the notify command of the config with the message as its last argument
+fn notify(config: &Config, message: &str) -> Result<(), CustomErrors> {
+    let (program, args) = match config.notify_command.split_first() {
+        Some(val) => val,
+        None => {
+            return Err(CustomErrors::InvalidInput(
+                "The notify_command of the config is empty".to_owned(),
+            ))
+        }
+    };
+
+    match Command::new(program).args(args).arg(message).status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(CustomErrors::Others(format!(
+            "The notify command {} failed with {}",
+            program, status
+        ))),
+        Err(e) => Err(CustomErrors::Io(
+            format!("Couldn't run the notify command {}", program),
+            e,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    fn config(daily_goal: u32) -> Config {
+        Config {
+            daily_goal,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn reminds_of_the_daily_goal() -> Result<(), CustomErrors> {
+        let db = Db::open_in_memory()?;
+        db.add_link("https://a.com/1".to_owned())?;
+        db.add_link("https://a.com/2".to_owned())?;
+        db.mark_as_complete("https://a.com/1")?;
+
+        assert_eq!(
+            reminder_message(&db, &config(2))?,
+            Some("1 of 2 links done today, next: https://a.com/2".to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn stays_quiet_once_the_goal_is_met() -> Result<(), CustomErrors> {
+        let db = Db::open_in_memory()?;
+        db.add_link("https://a.com/1".to_owned())?;
+        db.mark_as_complete("https://a.com/1")?;
+
+        assert_eq!(reminder_message(&db, &config(1))?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn passes_the_message_to_the_notify_command() -> Result<(), CustomErrors> {
+        let output = env::temp_dir().join(format!("abhyas-notify-{}", process::id()));
+        let config = Config {
+            notify_command: vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                format!("printf %s \"$1\" > '{}'", output.display()),
+                "sh".to_owned(),
+            ],
+            ..Config::default()
+        };
+
+        notify(&config, "1 of 2 links done today")?;
+
+        assert_eq!(
+            fs::read_to_string(&output).ok().as_deref(),
+            Some("1 of 2 links done today")
+        );
+        let _ = fs::remove_file(output);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reports_a_failing_notify_command() {
+        let config = Config {
+            notify_command: vec!["false".to_owned()],
+            ..Config::default()
+        };
+
+        assert!(matches!(
+            notify(&config, "message"),
+            Err(CustomErrors::Others(_))
+        ));
+    }
+}
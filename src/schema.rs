@@ -0,0 +1,227 @@
+use crate::CustomErrors;
+use rusqlite::Connection;
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+
+/// path of `abhyas.db` in the abhyas cache directory, which may not exist yet
+pub(crate) fn db_file_path() -> Result<PathBuf, CustomErrors> {
+    match dirs::cache_dir() {
+        Some(value) => Ok(value.join("abhyas").join("abhyas.db")),
+        None => Err(CustomErrors::CacheDirectoryNotFound),
+    }
+}
+
+/// create directory and file to store the db, returns the path of the db file
+pub(crate) fn create_db_file() -> Result<PathBuf, CustomErrors> {
+    let file_name = &db_file_path()?;
+    if let Some(dir_name) = file_name.parent() {
+        if let Err(e) = fs::create_dir_all(dir_name) {
+            return Err(CustomErrors::CreateDirectoryFailed(e));
+        }
+    }
+
+    match OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(file_name)
+    {
+        Ok(_) => (),
+        Err(e) => return Err(CustomErrors::FileCreationFailed(e)),
+    }
+
+    Ok(file_name.to_path_buf())
+}
+
+/// create the tables and indexes, upgrading databases created by older versions
+pub(crate) fn create_tables(conn: &Connection) -> Result<(), CustomErrors> {
+    if let Err(e) = conn.execute(
+        "CREATE TABLE IF NOT EXISTS links (
+            link            TEXT PRIMARY KEY,
+            solved_count    INTEGER NOT NULL,
+            is_solved       INTEGER NOT NULL,
+            is_skipped      INTEGER NOT NULL
+        )",
+        (),
+    ) {
+        return Err(CustomErrors::DBQueryFailed(e));
+    }
+
+    if let Err(e) = conn.execute(
+        "CREATE TABLE IF NOT EXISTS link_tags (
+            link            TEXT NOT NULL,
+            tag             TEXT NOT NULL,
+            PRIMARY KEY (link, tag)
+        )",
+        (),
+    ) {
+        return Err(CustomErrors::DBQueryFailed(e));
+    }
+
+    if let Err(e) = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS collections (
+            name            TEXT PRIMARY KEY
+        );
+        CREATE TABLE IF NOT EXISTS collection_links (
+            collection      TEXT NOT NULL,
+            link            TEXT NOT NULL,
+            position        INTEGER NOT NULL,
+            PRIMARY KEY (collection, link)
+        );",
+    ) {
+        return Err(CustomErrors::DBQueryFailed(e));
+    }
+
+    if let Err(e) = conn.execute(
+        "CREATE TABLE IF NOT EXISTS history (
+            link            TEXT NOT NULL,
+            action          TEXT NOT NULL,
+            at              TEXT NOT NULL
+        )",
+        (),
+    ) {
+        return Err(CustomErrors::DBQueryFailed(e));
+    }
+
+    if let Err(e) = conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS sessions (
+            id              INTEGER PRIMARY KEY,
+            started_at      TEXT NOT NULL,
+            minutes         INTEGER NOT NULL,
+            strategy        TEXT NOT NULL,
+            tag             TEXT
+        );
+        CREATE TABLE IF NOT EXISTS session_links (
+            session_id      INTEGER NOT NULL,
+            position        INTEGER NOT NULL,
+            link            TEXT NOT NULL,
+            outcome         TEXT NOT NULL,
+            duration_secs   INTEGER NOT NULL,
+            PRIMARY KEY (session_id, position)
+        );",
+    ) {
+        return Err(CustomErrors::DBQueryFailed(e));
+    }
+
+    add_column_if_missing(conn, "links", "completed_at", "TEXT")?;
+    add_column_if_missing(conn, "links", "title", "TEXT NOT NULL DEFAULT ''")?;
+    add_column_if_missing(conn, "links", "notes", "TEXT NOT NULL DEFAULT ''")?;
+    add_column_if_missing(conn, "history", "duration_secs", "INTEGER")?;
+    add_column_if_missing(conn, "links", "failed_count", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "links", "is_failed", "INTEGER NOT NULL DEFAULT 0")?;
+    add_column_if_missing(conn, "links", "skipped_at", "TEXT")?;
+    add_column_if_missing(conn, "links", "skipped_until", "TEXT")?;
+
+    // earlier versions left the snooze of links that were reset from skipped
+    if let Err(e) = conn.execute(
+        "UPDATE links SET skipped_at = NULL, skipped_until = NULL
+        WHERE is_skipped = 0 AND (skipped_at IS NOT NULL OR skipped_until IS NOT NULL)",
+        (),
+    ) {
+        return Err(CustomErrors::DBQueryFailed(e));
+    }
+
+    create_search_index(conn)?;
+
+    Ok(())
+}
+
+/// create the full-text search index over link, title, notes and tags,
+/// the triggers keep it in sync with the links and link_tags tables
+fn create_search_index(conn: &Connection) -> Result<(), CustomErrors> {
+    if let Err(e) = conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS links_fts USING fts5(link, title, notes, tags);
+
+        CREATE TRIGGER IF NOT EXISTS links_fts_insert AFTER INSERT ON links BEGIN
+            INSERT INTO links_fts (rowid, link, title, notes, tags)
+            VALUES (new.rowid, new.link, new.title, new.notes, '');
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS links_fts_delete AFTER DELETE ON links BEGIN
+            DELETE FROM links_fts WHERE rowid = old.rowid;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS links_fts_update
+        AFTER UPDATE OF link, title, notes ON links BEGIN
+            UPDATE links_fts SET link = new.link, title = new.title, notes = new.notes
+            WHERE rowid = new.rowid;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS links_fts_tag_insert AFTER INSERT ON link_tags BEGIN
+            UPDATE links_fts
+            SET tags = (SELECT group_concat(tag, ' ') FROM link_tags WHERE link = new.link)
+            WHERE rowid = (SELECT rowid FROM links WHERE link = new.link);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS links_fts_tag_delete AFTER DELETE ON link_tags BEGIN
+            UPDATE links_fts
+            SET tags = COALESCE(
+                (SELECT group_concat(tag, ' ') FROM link_tags WHERE link = old.link),
+                ''
+            )
+            WHERE rowid = (SELECT rowid FROM links WHERE link = old.link);
+        END;",
+    ) {
+        return Err(CustomErrors::DBQueryFailed(e));
+    }
+
+    // databases created before the search index existed need a one time backfill
+    let (indexed_count, links_count): (i64, i64) = match conn.query_row(
+        "SELECT (SELECT COUNT(*) FROM links_fts), (SELECT COUNT(*) FROM links)",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ) {
+        Ok(val) => val,
+        Err(e) => return Err(CustomErrors::DBQueryFailed(e)),
+    };
+
+    if indexed_count == links_count {
+        return Ok(());
+    }
+
+    match conn.execute_batch(
+        "BEGIN;
+        DELETE FROM links_fts;
+        INSERT INTO links_fts (rowid, link, title, notes, tags)
+        SELECT rowid, link, title, notes,
+            COALESCE((SELECT group_concat(tag, ' ') FROM link_tags WHERE link_tags.link = links.link), '')
+        FROM links;
+        COMMIT;",
+    ) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(CustomErrors::DBQueryFailed(e)),
+    }
+}
+
+/// add a new column to an existing table, used to upgrade databases created by older versions
+fn add_column_if_missing(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), CustomErrors> {
+    let mut stmt = match conn.prepare(&format!("PRAGMA table_info({})", table)) {
+        Ok(val) => val,
+        Err(e) => return Err(CustomErrors::StatementFailed(e)),
+    };
+
+    let columns = match stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .and_then(|rows| rows.collect::<Result<Vec<String>, _>>())
+    {
+        Ok(val) => val,
+        Err(e) => return Err(CustomErrors::DBQueryFailed(e)),
+    };
+
+    if columns.iter().any(|name| name == column) {
+        return Ok(());
+    }
+
+    match conn.execute(
+        &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+        (),
+    ) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(CustomErrors::DBQueryFailed(e)),
+    }
+}
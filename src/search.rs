@@ -0,0 +1,142 @@
+use crate::CustomErrors;
+
+/// link state that can be searched with the `status:` prefix
+pub enum StatusFilter {
+    Pending,
+    Completed,
+    Skipped,
+    Failed,
+}
+
+/// parsed search input, free terms go through the full-text index while
+/// `field:value` prefixes narrow down the results
+#[derive(Default)]
+pub struct SearchQuery {
+    /// words matched against every indexed column
+    pub terms: Vec<String>,
+    /// words matched against a single column of the index
+    pub column_terms: Vec<(&'static str, String)>,
+    /// links must have every one of these tags
+    pub tags: Vec<String>,
+    pub status: Option<StatusFilter>,
+    pub platform: Option<String>,
+    pub collection: Option<String>,
+}
+
+/// short description of the supported prefixes, shown next to search prompts
+pub const SEARCH_HELP: &str =
+    "prefixes: tag:, status:(pending|completed|skipped|failed), platform:, collection:, url:, title:, notes:";
+
+impl SearchQuery {
+    /// split the input on whitespace and sort every word into a term or a filter
+    pub fn parse(input: &str) -> Result<Self, CustomErrors> {
+        let mut query = SearchQuery::default();
+
+        for word in input.split_whitespace() {
+            let (prefix, value) = match word.split_once(':') {
+                Some((prefix, value)) if !value.is_empty() => (prefix.to_lowercase(), value),
+                _ => (String::new(), word),
+            };
+
+            match prefix.as_str() {
+                "tag" => query.tags.push(value.to_owned()),
+                "status" => {
+                    query.status = Some(match value.to_lowercase().as_str() {
+                        "pending" | "incomplete" => StatusFilter::Pending,
+                        "completed" | "complete" | "solved" => StatusFilter::Completed,
+                        "skipped" => StatusFilter::Skipped,
+                        "failed" => StatusFilter::Failed,
+                        _ => {
+                            return Err(CustomErrors::InvalidInput(format!(
+                                "Unknown status {}, use pending, completed, skipped or failed",
+                                value
+                            )))
+                        }
+                    })
+                }
+                "platform" => query.platform = Some(value.to_owned()),
+                "collection" => query.collection = Some(value.to_owned()),
+                "url" | "link" => query.column_terms.push(("link", value.to_owned())),
+                "title" => query.column_terms.push(("title", value.to_owned())),
+                "notes" | "note" => query.column_terms.push(("notes", value.to_owned())),
+                _ => query.terms.push(word.to_owned()),
+            }
+        }
+
+        Ok(query)
+    }
+
+    /// builds the fts5 MATCH expression, every term is quoted and prefix matched
+    pub fn match_expression(&self) -> Option<String> {
+        let quote = |term: &str| format!("\"{}\"*", term.replace('"', "\"\""));
+
+        // terms without any letters or digits produce no tokens and break the fts5 syntax
+        let searchable = |term: &&str| term.chars().any(char::is_alphanumeric);
+
+        let mut parts: Vec<String> = self
+            .terms
+            .iter()
+            .map(String::as_str)
+            .filter(searchable)
+            .map(quote)
+            .collect();
+        for (column, term) in &self.column_terms {
+            if searchable(&term.as_str()) {
+                parts.push(format!("{} : {}", column, quote(term)));
+            }
+        }
+
+        if parts.is_empty() {
+            None
+        } else {
+            Some(parts.join(" "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_words_into_terms_and_filters() -> Result<(), CustomErrors> {
+        let query = SearchQuery::parse("two Tag:dp tag:array status:solved title:sum url:leet")?;
+
+        assert_eq!(query.terms, vec!["two"]);
+        assert_eq!(query.tags, vec!["dp", "array"]);
+        assert!(matches!(query.status, Some(StatusFilter::Completed)));
+        assert_eq!(
+            query.column_terms,
+            vec![("title", "sum".to_owned()), ("link", "leet".to_owned())]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_an_unknown_status() {
+        assert!(matches!(
+            SearchQuery::parse("status:done"),
+            Err(CustomErrors::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn quotes_the_match_terms() -> Result<(), CustomErrors> {
+        let query = SearchQuery::parse("two \"sum title:dp")?;
+
+        assert_eq!(
+            query.match_expression(),
+            Some(r#""two"* """sum"* title : "dp"*"#.to_owned())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn skips_terms_without_letters_or_digits() -> Result<(), CustomErrors> {
+        assert_eq!(SearchQuery::parse("- ** tag:dp")?.match_expression(), None);
+
+        Ok(())
+    }
+}
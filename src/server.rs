@@ -0,0 +1,294 @@
+use crate::commands::existing_link;
+use crate::print::{JsonError, NO_PENDING_LINKS};
+use crate::utility::{show_error, show_green, show_warnings};
+use abhyas::database::Db;
+use abhyas::error::EXIT_DATABASE_LOCKED;
+use abhyas::link::{Link, LinkFilter, LinkSort, LinkState};
+use abhyas::CustomErrors;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// body of every request that changes a single link
+#[derive(Deserialize)]
+struct LinkBody {
+    link: String,
+}
+
+/// offline dashboard served on `/`, it only talks to the json api below
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+const JSON: &str = "application/json; charset=utf-8";
+const HTML: &str = "text/html; charset=utf-8";
+
+/// status code, content type and body of a response
+type Reply = (u16, &'static str, String);
+
+/// serve the db as a json api on localhost until the process is killed
+///
+/// requests are handled one at a time on a single connection, other abhyas
+/// processes share the same db file and wait for each other through sqlite locks
+pub fn serve(db: &Db, port: u16) -> Result<(), CustomErrors> {
+    let server = match Server::http(("127.0.0.1", port)) {
+        Ok(val) => val,
+        Err(e) => {
+            return Err(CustomErrors::Context(
+                format!("Couldn't listen on port {}", port),
+                e,
+            ))
+        }
+    };
+
+    show_green(format!("Serving the abhyas dashboard on http://127.0.0.1:{}", port).as_str());
+
+    for mut request in server.incoming_requests() {
+        let (status, kind, body) = if is_local_host(&request, port) {
+            handle_request(db, &mut request)
+        } else {
+            error_reply(403, "Forbidden host")
+        };
+        show_warnings(db);
+        let response = Response::from_string(body)
+            .with_status_code(status)
+            .with_header(content_type(kind));
+
+        if let Err(e) = request.respond(response) {
+            show_error(&format!("Error: Couldn't send the response, {}", e));
+        }
+    }
+
+    Ok(())
+}
+
+/// returns true when the Host header names this server, a page of another site
+/// that rebinds its dns name to 127.0.0.1 still sends its own host and is refused
+fn is_local_host(request: &Request, port: u16) -> bool {
+    let host = match request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Host"))
+    {
+        Some(header) => header.value.as_str(),
+        None => return false,
+    };
+
+    host == format!("127.0.0.1:{}", port) || host == format!("localhost:{}", port)
+}
+
+fn content_type(value: &str) -> Header {
+    Header::from_bytes("Content-Type", value).expect("the content type header is valid")
+}
+
+fn handle_request(db: &Db, request: &mut Request) -> Reply {
+    let url = request.url().to_owned();
+    let (path, query) = url.split_once('?').unwrap_or((&url, ""));
+    let params = parse_query(query);
+
+    let result = match (request.method(), path) {
+        (Method::Get, "/") => return (200, HTML, DASHBOARD.to_owned()),
+        (Method::Get, "/status") => db.get_status_summary().and_then(|val| to_json(&val)),
+        (Method::Get, "/stats") => db.get_stats().and_then(|val| to_json(&val)),
+        (Method::Get, "/links") => list_links(db, &params),
+        (Method::Get, "/links/next") => match next_link(db, &params) {
+            Ok(Some(link)) => to_json(&link),
+            Ok(None) => return error_reply(404, NO_PENDING_LINKS),
+            Err(e) => Err(e),
+        },
+        (Method::Post, "/links") => read_link_body(request).and_then(|link| {
+            db.add_link(link.clone())?;
+            to_json(&existing_link(db, &link)?)
+        }),
+        (Method::Post, "/links/complete") => read_link_body(request).and_then(|link| {
+            existing_link(db, &link)?;
+            db.mark_as_complete(&link)?;
+            to_json(&existing_link(db, &link)?)
+        }),
+        (Method::Post, "/links/skip") => read_link_body(request).and_then(|link| {
+            existing_link(db, &link)?;
+            db.skip_link(&link)?;
+            to_json(&existing_link(db, &link)?)
+        }),
+        (Method::Post, "/links/fail") => read_link_body(request).and_then(|link| {
+            existing_link(db, &link)?;
+            db.mark_as_failed(&link)?;
+            to_json(&existing_link(db, &link)?)
+        }),
+        (Method::Delete, "/links") => read_link_body(request).and_then(|link| {
+            let details = existing_link(db, &link)?;
+            db.delete_link(link)?;
+            to_json(&details)
+        }),
+        (
+            _,
+            "/" | "/status" | "/stats" | "/links" | "/links/next" | "/links/complete"
+            | "/links/skip" | "/links/fail",
+        ) => return error_reply(405, "Method not allowed"),
+        _ => return error_reply(404, "Not found"),
+    };
+
+    match result {
+        Ok(body) if request.method() == &Method::Post && path == "/links" => (201, JSON, body),
+        Ok(body) => (200, JSON, body),
+        Err(e) => {
+            let status = match e {
+                CustomErrors::InvalidInput(_) => 400,
+                CustomErrors::LinkNotFound(_) => 404,
+                CustomErrors::DuplicateLinkValue => 409,
+                _ if e.exit_code() == EXIT_DATABASE_LOCKED => 503,
+                _ => 500,
+            };
+            error_reply(status, &e.to_string())
+        }
+    }
+}
+
+fn list_links(db: &Db, params: &HashMap<String, String>) -> Result<String, CustomErrors> {
+    let state = match params.get("state").map(String::as_str) {
+        None => None,
+        Some("pending") => Some(LinkState::Pending),
+        Some("completed") => Some(LinkState::Completed),
+        Some("skipped") => Some(LinkState::Skipped),
+        Some("failed") => Some(LinkState::Failed),
+        Some(other) => {
+            return Err(CustomErrors::InvalidInput(format!(
+                "Unknown state {}, expected pending, completed, skipped or failed",
+                other
+            )))
+        }
+    };
+    let sort = match params.get("sort").map(String::as_str) {
+        None | Some("added") => LinkSort::Added,
+        Some("link") => LinkSort::Link,
+        Some("solved-count") => LinkSort::SolvedCount,
+        Some("completed-at") => LinkSort::CompletedAt,
+        Some("least-recently-completed") => LinkSort::LeastRecentlyCompleted,
+        Some("least-solved") => LinkSort::LeastSolved,
+        Some("random") => LinkSort::Random,
+        Some(other) => {
+            return Err(CustomErrors::InvalidInput(format!(
+                "Unknown sort {}, expected added, link, solved-count, completed-at, least-recently-completed, least-solved or random",
+                other
+            )))
+        }
+    };
+
+    let filter = LinkFilter {
+        state,
+        tag: params.get("tag").cloned(),
+        collection: params.get("collection").cloned(),
+    };
+    let limit = parse_number(params, "limit")?;
+    let offset = parse_number(params, "offset")?.unwrap_or(0);
+
+    to_json(&db.query_links(&filter, sort, limit, offset)?)
+}
+
+fn next_link(db: &Db, params: &HashMap<String, String>) -> Result<Option<Link>, CustomErrors> {
+    match params.get("collection") {
+        Some(collection) => db.get_next_collection_link(collection),
+        None => db.get_single_link(),
+    }
+}
+
+fn parse_number(
+    params: &HashMap<String, String>,
+    name: &str,
+) -> Result<Option<usize>, CustomErrors> {
+    match params.get(name) {
+        Some(value) => match value.parse() {
+            Ok(val) => Ok(Some(val)),
+            Err(_) => Err(CustomErrors::InvalidInput(format!(
+                "Invalid {} {}, expected a positive number",
+                name, value
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+
+/// read the `{"link": "..."}` body, only json bodies are accepted so that other
+/// websites can't post to the api without a cors preflight
+fn read_link_body(request: &mut Request) -> Result<String, CustomErrors> {
+    let is_json = request.headers().iter().any(|header| {
+        header.field.equiv("Content-Type") && header.value.as_str().starts_with("application/json")
+    });
+    if !is_json {
+        return Err(CustomErrors::InvalidInput(
+            "Expected a json body with the application/json content type".to_owned(),
+        ));
+    }
+
+    let mut body = String::new();
+    if let Err(e) = request.as_reader().read_to_string(&mut body) {
+        return Err(CustomErrors::Io(
+            "Something went wrong while reading the request body".to_owned(),
+            e,
+        ));
+    }
+
+    match serde_json::from_str::<LinkBody>(&body) {
+        Ok(val) if !val.link.trim().is_empty() => Ok(val.link.trim().to_owned()),
+        Ok(_) => Err(CustomErrors::InvalidInput(
+            "The link can't be empty".to_owned(),
+        )),
+        Err(e) => Err(CustomErrors::InvalidInput(format!(
+            "Invalid request body, expected {{\"link\": \"...\"}}: {}",
+            e
+        ))),
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, CustomErrors> {
+    match serde_json::to_string(value) {
+        Ok(val) => Ok(val),
+        Err(e) => Err(CustomErrors::Context(
+            "Something went wrong while writing the json response".to_owned(),
+            Box::new(e),
+        )),
+    }
+}
+
+fn error_reply(status: u16, message: &str) -> Reply {
+    let body = JsonError {
+        error: message.to_owned(),
+    };
+    (status, JSON, to_json(&body).unwrap_or_default())
+}
+
+/// split `a=1&b=two%20words` into decoded key value pairs
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'+' => decoded.push(b' '),
+            b'%' if index + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[index + 1..index + 3]);
+                match hex.map(|hex| u8::from_str_radix(hex, 16)) {
+                    Ok(Ok(byte)) => {
+                        decoded.push(byte);
+                        index += 2;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        index += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
@@ -0,0 +1,71 @@
+use serde::Serialize;
+use std::fmt;
+
+/// how a single link of a practice session ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionOutcome {
+    Solved,
+    /// given up on, or still open when the session ran out of time
+    Failed,
+    /// not attempted
+    Skipped,
+}
+
+impl SessionOutcome {
+    /// parse the value stored in the `session_links` table
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "solved" => Some(SessionOutcome::Solved),
+            "failed" => Some(SessionOutcome::Failed),
+            "skipped" => Some(SessionOutcome::Skipped),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SessionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionOutcome::Solved => write!(f, "solved"),
+            SessionOutcome::Failed => write!(f, "failed"),
+            SessionOutcome::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// a link of a session with its outcome and the time spent on it
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionLink {
+    pub link: String,
+    pub outcome: SessionOutcome,
+    pub duration_secs: i64,
+}
+
+/// a timed practice session, the json shape of `abhyas sessions`
+#[derive(Clone, Debug, Serialize)]
+pub struct Session {
+    pub id: i64,
+    /// utc time as `YYYY-MM-DD HH:MM:SS`
+    pub started_at: String,
+    pub minutes: u32,
+    pub strategy: String,
+    pub tag: Option<String>,
+    /// in the order they were practiced
+    pub links: Vec<SessionLink>,
+}
+
+impl Session {
+    /// number of links with the given outcome
+    pub fn count(&self, outcome: SessionOutcome) -> usize {
+        self.links
+            .iter()
+            .filter(|link| link.outcome == outcome)
+            .count()
+    }
+
+    /// time spent on all links together
+    pub fn total_secs(&self) -> i64 {
+        self.links.iter().map(|link| link.duration_secs).sum()
+    }
+}
@@ -0,0 +1,55 @@
+use serde::Serialize;
+
+/// link counts of the whole db, the json shape of `abhyas status`
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Status {
+    pub total: i32,
+    pub completed: i32,
+    pub skipped: i32,
+    /// neither completed nor skipped, with at least one failed attempt since the last solve
+    pub failed: i32,
+    /// neither completed, skipped nor failed
+    pub pending: i32,
+}
+
+/// link counts of a single collection
+#[derive(Clone, Debug, Serialize)]
+pub struct CollectionStatus {
+    pub name: String,
+    pub total: i32,
+    pub completed: i32,
+    pub skipped: i32,
+}
+
+/// how many links were completed and skipped on a single day
+#[derive(Clone, Debug, Serialize)]
+pub struct DayActivity {
+    /// utc date as `YYYY-MM-DD`
+    pub date: String,
+    pub completed: i32,
+    pub skipped: i32,
+}
+
+/// timed attempts of a single link or of every link with a tag
+#[derive(Clone, Debug, Serialize)]
+pub struct SolveTime {
+    /// the link or the tag
+    pub name: String,
+    /// number of completions that were timed
+    pub attempts: i32,
+    pub average_secs: i64,
+    pub best_secs: i64,
+}
+
+/// overall progress, the json shape of `abhyas stats`
+#[derive(Clone, Debug, Serialize)]
+pub struct Stats {
+    pub status: Status,
+    pub collections: Vec<CollectionStatus>,
+    /// days with any activity in the last year, oldest first
+    pub activity: Vec<DayActivity>,
+    /// links with at least one timed completion, sorted by link
+    pub link_times: Vec<SolveTime>,
+    /// tags with at least one timed completion, sorted by tag
+    pub tag_times: Vec<SolveTime>,
+}
@@ -0,0 +1,350 @@
+use crate::database::Db;
+use crate::CustomErrors;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// file with one `LinkRecord` per line, sorted by link
+pub const LINKS_FILE: &str = "links.jsonl";
+/// file with one `CollectionRecord` per line, sorted by name
+pub const COLLECTIONS_FILE: &str = "collections.jsonl";
+
+/// a single entry of the practice history, ordered by time
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// utc time as `YYYY-MM-DD HH:MM:SS`
+    pub at: String,
+    pub action: String,
+    /// how long a timed completion took
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<i64>,
+}
+
+/// everything stored about a link, one line of `links.jsonl`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LinkRecord {
+    pub link: String,
+    pub title: String,
+    pub notes: String,
+    pub solved_count: i32,
+    #[serde(default)]
+    pub failed_count: i32,
+    pub completed: bool,
+    pub skipped: bool,
+    #[serde(default)]
+    pub failed: bool,
+    pub completed_at: Option<String>,
+    /// when a snoozed link goes back to the pool
+    #[serde(default)]
+    pub skipped_until: Option<String>,
+    /// sorted alphabetically
+    pub tags: Vec<String>,
+    /// oldest first
+    pub history: Vec<HistoryEntry>,
+}
+
+/// a collection with its links in order, one line of `collections.jsonl`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CollectionRecord {
+    pub name: String,
+    pub links: Vec<String>,
+}
+
+/// how the counters and state of a link present on both sides are reconciled,
+/// tags and history are always merged
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// keep the higher solved count and the state of the side practiced last
+    #[default]
+    Max,
+    /// count every completion in the history of either side once and keep the state
+    /// of the side practiced last, so merging the same db again changes nothing, the
+    /// part of a count older than the history can't be matched up and keeps the higher one
+    Union,
+    /// keep the local counters, state and details, only fill in what is missing
+    PreferLocal,
+    /// take the remote counters, state and details, keep local details the remote lacks
+    PreferRemote,
+}
+
+impl MergePolicy {
+    /// returns the local record with its counters, state and details reconciled
+    /// with the remote one, tags and history are left untouched
+    pub fn merge(&self, local: &LinkRecord, remote: &LinkRecord) -> LinkRecord {
+        let (preferred, other) = match self {
+            MergePolicy::PreferRemote => (remote, local),
+            _ => (local, remote),
+        };
+        let pick = |preferred: &String, other: &String| {
+            if preferred.is_empty() {
+                other.clone()
+            } else {
+                preferred.clone()
+            }
+        };
+
+        // the side with the most recent history entry knows the current state
+        let latest = if remote.history.last() > local.history.last() {
+            remote
+        } else {
+            local
+        };
+        let state_from = match self {
+            MergePolicy::Max | MergePolicy::Union => latest,
+            MergePolicy::PreferLocal | MergePolicy::PreferRemote => preferred,
+        };
+
+        let (solved_count, failed_count, completed_at) = match self {
+            MergePolicy::Max => (
+                local.solved_count.max(remote.solved_count),
+                local.failed_count.max(remote.failed_count),
+                local.completed_at.clone().max(remote.completed_at.clone()),
+            ),
+            MergePolicy::Union => (
+                union_counts(
+                    local,
+                    remote,
+                    local.solved_count,
+                    remote.solved_count,
+                    "complete",
+                ),
+                union_counts(
+                    local,
+                    remote,
+                    local.failed_count,
+                    remote.failed_count,
+                    "fail",
+                ),
+                local.completed_at.clone().max(remote.completed_at.clone()),
+            ),
+            MergePolicy::PreferLocal | MergePolicy::PreferRemote => (
+                preferred.solved_count,
+                preferred.failed_count,
+                preferred
+                    .completed_at
+                    .clone()
+                    .or_else(|| other.completed_at.clone()),
+            ),
+        };
+
+        LinkRecord {
+            title: pick(&preferred.title, &other.title),
+            notes: pick(&preferred.notes, &other.notes),
+            solved_count,
+            failed_count,
+            completed: state_from.completed,
+            skipped: state_from.skipped,
+            failed: state_from.failed,
+            skipped_until: state_from.skipped_until.clone(),
+            completed_at,
+            ..local.clone()
+        }
+    }
+}
+
+/// returns the count of both sides with every history entry of the action counted once,
+/// the part of a count that has no history entries, e.g. from before the history was
+/// kept, can't be matched up between the sides so the higher one is kept
+fn union_counts(
+    local: &LinkRecord,
+    remote: &LinkRecord,
+    local_count: i32,
+    remote_count: i32,
+    action: &str,
+) -> i32 {
+    let entries = |record: &LinkRecord| {
+        record
+            .history
+            .iter()
+            .filter(|entry| entry.action == action)
+            .count() as i32
+    };
+    let new_entries = remote
+        .history
+        .iter()
+        .filter(|entry| entry.action == action && !local.history.contains(entry))
+        .count() as i32;
+
+    let untracked = (local_count - entries(local))
+        .max(remote_count - entries(remote))
+        .max(0);
+
+    entries(local) + new_entries + untracked
+}
+
+/// what an import changed, or would change on a dry run
+#[derive(Clone, Debug, Default)]
+pub struct SyncSummary {
+    pub added_links: usize,
+    pub updated_links: usize,
+    pub added_history: usize,
+    pub added_collection_links: usize,
+    /// every link that was added or updated
+    pub links: Vec<String>,
+}
+
+/// write the db as `links.jsonl` and `collections.jsonl` into the directory,
+/// the output only changes when the data does so it can be committed to git
+///
+/// returns the number of links written
+pub fn export_to_dir(db: &Db, dir: &Path) -> Result<usize, CustomErrors> {
+    if let Err(e) = fs::create_dir_all(dir) {
+        return Err(CustomErrors::Io(
+            format!("Couldn't create the sync directory {}", dir.display()),
+            e,
+        ));
+    }
+
+    let links = db.get_link_records()?;
+    write_lines(&dir.join(LINKS_FILE), &links)?;
+    write_lines(&dir.join(COLLECTIONS_FILE), &db.get_collection_records()?)?;
+
+    Ok(links.len())
+}
+
+/// merge the files written by `export_to_dir` into the db, links present on
+/// both sides keep the higher counters and the union of their histories
+///
+/// nothing is written when `dry_run` is set, the summary is still returned
+pub fn import_from_dir(db: &Db, dir: &Path, dry_run: bool) -> Result<SyncSummary, CustomErrors> {
+    let links: Vec<LinkRecord> = read_lines(&dir.join(LINKS_FILE))?;
+
+    let collections_file = dir.join(COLLECTIONS_FILE);
+    let collections: Vec<CollectionRecord> = if collections_file.exists() {
+        read_lines(&collections_file)?
+    } else {
+        vec![]
+    };
+
+    db.merge_records(&links, &collections, MergePolicy::Max, dry_run)
+}
+
+fn write_lines<T: Serialize>(file: &Path, records: &[T]) -> Result<(), CustomErrors> {
+    let mut output = String::new();
+    for record in records {
+        match serde_json::to_string(record) {
+            Ok(line) => output.push_str(&line),
+            Err(e) => {
+                return Err(CustomErrors::Context(
+                    format!("Couldn't serialize the records of {}", file.display()),
+                    Box::new(e),
+                ))
+            }
+        };
+        output.push('\n');
+    }
+
+    match fs::write(file, output) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(CustomErrors::Io(
+            format!("Couldn't write {}", file.display()),
+            e,
+        )),
+    }
+}
+
+fn read_lines<T: for<'de> Deserialize<'de>>(file: &Path) -> Result<Vec<T>, CustomErrors> {
+    let data = match fs::read_to_string(file) {
+        Ok(val) => val,
+        Err(e) => {
+            return Err(CustomErrors::Io(
+                format!("Couldn't read {}", file.display()),
+                e,
+            ))
+        }
+    };
+
+    data.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| match serde_json::from_str(line) {
+            Ok(val) => Ok(val),
+            Err(e) => Err(CustomErrors::InvalidInput(format!(
+                "Invalid record on line {} of {}: {}",
+                index + 1,
+                file.display(),
+                e
+            ))),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(link: &str, solved_count: i32, completions: &[&str]) -> LinkRecord {
+        LinkRecord {
+            link: link.to_owned(),
+            title: String::new(),
+            notes: String::new(),
+            solved_count,
+            failed_count: 0,
+            completed: solved_count > 0,
+            skipped: false,
+            failed: false,
+            completed_at: completions.last().map(|at| at.to_string()),
+            skipped_until: None,
+            tags: vec![],
+            history: completions
+                .iter()
+                .map(|at| HistoryEntry {
+                    at: at.to_string(),
+                    action: "complete".to_owned(),
+                    duration_secs: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn union_counts_shared_completions_once() {
+        let local = record("a", 1, &["2024-01-01 10:00:00"]);
+        let remote = record("a", 1, &["2024-01-01 10:00:00"]);
+
+        assert_eq!(MergePolicy::Union.merge(&local, &remote).solved_count, 1);
+    }
+
+    #[test]
+    fn union_adds_new_completions() {
+        let local = record("a", 1, &["2024-01-01 10:00:00"]);
+        let remote = record("a", 2, &["2024-01-01 10:00:00", "2024-01-02 10:00:00"]);
+
+        assert_eq!(MergePolicy::Union.merge(&local, &remote).solved_count, 2);
+    }
+
+    #[test]
+    fn union_keeps_the_higher_count_without_history() {
+        let local = record("a", 2, &[]);
+        let remote = record("a", 3, &["2024-01-01 10:00:00"]);
+
+        assert_eq!(MergePolicy::Union.merge(&local, &remote).solved_count, 3);
+    }
+
+    #[test]
+    fn merging_twice_with_union_changes_nothing() -> Result<(), CustomErrors> {
+        let db = Db::open_in_memory()?;
+        db.add_link("a".to_owned())?;
+        db.mark_as_complete("a")?;
+
+        let remote = vec![
+            record("a", 1, &["2024-01-01 10:00:00"]),
+            record("3sum", 2, &[]),
+        ];
+        db.merge_records(&remote, &[], MergePolicy::Union, false)?;
+        let first = db.get_link_records()?;
+        let summary = db.merge_records(&remote, &[], MergePolicy::Union, false)?;
+
+        assert_eq!(db.get_link_records()?, first);
+        assert_eq!(summary.updated_links, 0);
+        assert_eq!(
+            first
+                .iter()
+                .map(|record| (record.link.as_str(), record.solved_count))
+                .collect::<Vec<_>>(),
+            vec![("3sum", 2), ("a", 2)]
+        );
+
+        Ok(())
+    }
+}
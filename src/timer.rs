@@ -0,0 +1,193 @@
+use abhyas::CustomErrors;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::terminal;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+/// how often the elapsed time is redrawn
+const TICK: Duration = Duration::from_millis(200);
+
+/// how the user left the timer
+pub enum TimerOutcome {
+    /// the link was solved in this many seconds
+    Completed(i64),
+    /// the timer was stopped without solving the link
+    Stopped,
+}
+
+/// how the user moved on from a link of a session
+pub enum CountdownOutcome {
+    Solved,
+    /// given up on
+    Failed,
+    Skipped,
+    /// the user ended the whole session
+    Ended,
+    /// the session ran out of time
+    TimeUp,
+}
+
+/// a stopwatch that can be paused and resumed
+struct Stopwatch {
+    running_since: Option<Instant>,
+    elapsed: Duration,
+}
+
+impl Stopwatch {
+    fn start() -> Self {
+        Stopwatch {
+            running_since: Some(Instant::now()),
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.elapsed + since.elapsed(),
+            None => self.elapsed,
+        }
+    }
+
+    fn toggle(&mut self) {
+        self.running_since = match self.running_since {
+            Some(since) => {
+                self.elapsed += since.elapsed();
+                None
+            }
+            None => Some(Instant::now()),
+        };
+    }
+}
+
+/// puts the terminal back into normal mode even when the timer fails
+struct RawModeGuard;
+
+impl RawModeGuard {
+    fn enable() -> Result<Self, CustomErrors> {
+        match terminal::enable_raw_mode() {
+            Ok(_) => Ok(RawModeGuard),
+            Err(e) => Err(CustomErrors::Io("Couldn't start the timer".to_owned(), e)),
+        }
+    }
+}
+
+impl Drop for RawModeGuard {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+        println!();
+    }
+}
+
+/// format seconds as `mm:ss`, or `h:mm:ss` from an hour on
+pub fn format_duration(secs: i64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+/// show a running timer on a single line until the link is completed or the timer stopped
+///
+/// space or p pauses and resumes, c or enter completes, q or escape stops without
+/// recording anything and ctrl-c quits the application
+pub fn run_timer() -> Result<TimerOutcome, CustomErrors> {
+    let _guard = RawModeGuard::enable()?;
+    let mut stopwatch = Stopwatch::start();
+
+    loop {
+        let state = match stopwatch.running_since {
+            Some(_) => "running",
+            None => "paused ",
+        };
+        print!(
+            "\r{} {}  [space] pause/resume  [c] complete  [q] stop",
+            format_duration(stopwatch.elapsed().as_secs() as i64),
+            state
+        );
+        let _ = io::stdout().flush();
+
+        let key = match read_key()? {
+            Some(val) => val,
+            None => continue,
+        };
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Err(CustomErrors::OperationInterrupted)
+            }
+            KeyCode::Char(' ') | KeyCode::Char('p') => stopwatch.toggle(),
+            KeyCode::Char('c') | KeyCode::Enter => {
+                return Ok(TimerOutcome::Completed(stopwatch.elapsed().as_secs() as i64))
+            }
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(TimerOutcome::Stopped),
+            _ => (),
+        };
+    }
+}
+
+/// count down the time left until the deadline while a link of a session is practiced,
+/// returns how the link ended and the seconds spent on it
+///
+/// s solves, f fails, k skips, q or escape ends the session and ctrl-c quits the application
+pub fn run_countdown(deadline: Instant) -> Result<(CountdownOutcome, i64), CustomErrors> {
+    let _guard = RawModeGuard::enable()?;
+    let started = Instant::now();
+
+    loop {
+        let elapsed = started.elapsed().as_secs() as i64;
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Ok((CountdownOutcome::TimeUp, elapsed));
+        }
+
+        print!(
+            "\r{} on this link, {} left  [s] solved  [f] failed  [k] skip  [q] end",
+            format_duration(elapsed),
+            format_duration(left.as_secs() as i64)
+        );
+        let _ = io::stdout().flush();
+
+        let key = match read_key()? {
+            Some(val) => val,
+            None => continue,
+        };
+
+        let outcome = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Err(CustomErrors::OperationInterrupted)
+            }
+            KeyCode::Char('s') => CountdownOutcome::Solved,
+            KeyCode::Char('f') => CountdownOutcome::Failed,
+            KeyCode::Char('k') => CountdownOutcome::Skipped,
+            KeyCode::Char('q') | KeyCode::Esc => CountdownOutcome::Ended,
+            _ => continue,
+        };
+        return Ok((outcome, started.elapsed().as_secs() as i64));
+    }
+}
+
+/// wait up to a tick for a key press
+fn read_key() -> Result<Option<KeyEvent>, CustomErrors> {
+    let ready = match event::poll(TICK) {
+        Ok(val) => val,
+        Err(e) => {
+            return Err(CustomErrors::Io(
+                "Couldn't read the key press".to_owned(),
+                e,
+            ))
+        }
+    };
+    if !ready {
+        return Ok(None);
+    }
+
+    match event::read() {
+        Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => Ok(Some(key)),
+        Ok(_) => Ok(None),
+        Err(e) => Err(CustomErrors::Io(
+            "Couldn't read the key press".to_owned(),
+            e,
+        )),
+    }
+}
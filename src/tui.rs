@@ -0,0 +1,349 @@
+use crate::utility::open_in_browser;
+use abhyas::database::{BulkAction, Db};
+use abhyas::link::{Link, LinkState};
+use abhyas::search::{SearchQuery, SEARCH_HELP};
+use abhyas::stats::Status;
+use abhyas::CustomErrors;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+/// maximum number of links loaded into the list at once
+const TUI_LINKS_LIMIT: usize = 10_000;
+
+const NORMAL_HELP: &str =
+    "j/k move  c complete  f failed  s skip  r reset  d delete  o open  / search  q quit";
+
+enum Mode {
+    Normal,
+    Search,
+    ConfirmDelete,
+}
+
+struct App<'a> {
+    db: &'a Db,
+    filter: String,
+    links: Vec<Link>,
+    list_state: ListState,
+    history: Vec<(String, String)>,
+    status: Status,
+    mode: Mode,
+    message: String,
+}
+
+impl<'a> App<'a> {
+    fn new(db: &'a Db) -> Self {
+        App {
+            db,
+            filter: String::new(),
+            links: vec![],
+            list_state: ListState::default(),
+            history: vec![],
+            status: Status::default(),
+            mode: Mode::Normal,
+            message: String::new(),
+        }
+    }
+
+    fn selected(&self) -> Option<&Link> {
+        self.list_state
+            .selected()
+            .and_then(|index| self.links.get(index))
+    }
+
+    fn selected_link(&self) -> Option<String> {
+        self.selected().map(|link| link.link.clone())
+    }
+
+    /// reload the links matching the current filter and the details of the selected link
+    fn refresh(&mut self) -> Result<(), CustomErrors> {
+        match SearchQuery::parse(&self.filter) {
+            Ok(query) => self.links = self.db.search_links(&query, TUI_LINKS_LIMIT)?,
+            Err(e) => self.message = format!("Error: {}", e),
+        };
+
+        self.status = self.db.get_status_summary()?;
+
+        let selected = match self.list_state.selected() {
+            _ if self.links.is_empty() => None,
+            Some(index) => Some(index.min(self.links.len() - 1)),
+            None => Some(0),
+        };
+        self.list_state.select(selected);
+
+        self.refresh_history()
+    }
+
+    fn refresh_history(&mut self) -> Result<(), CustomErrors> {
+        self.history = match self.selected_link() {
+            Some(link) => self.db.get_link_history(&link)?,
+            None => vec![],
+        };
+        Ok(())
+    }
+
+    fn move_selection(&mut self, offset: isize) -> Result<(), CustomErrors> {
+        if self.links.is_empty() {
+            return Ok(());
+        }
+
+        let current = self.list_state.selected().unwrap_or(0) as isize;
+        let last = self.links.len() as isize - 1;
+        self.list_state
+            .select(Some((current + offset).clamp(0, last) as usize));
+
+        self.refresh_history()
+    }
+
+    /// run the action on the selected link and show its outcome in the message bar
+    fn apply<F>(&mut self, action: F, success: &str) -> Result<(), CustomErrors>
+    where
+        F: FnOnce(&Db, &str) -> Result<(), CustomErrors>,
+    {
+        let link = match self.selected_link() {
+            Some(val) => val,
+            None => return Ok(()),
+        };
+
+        self.message = match action(self.db, &link) {
+            Ok(_) => success.to_owned(),
+            Err(e) => format!("Error: {}", e),
+        };
+
+        self.refresh()
+    }
+
+    /// handle a single key press, returns true when the user wants to quit
+    fn handle_key(&mut self, key: KeyEvent) -> Result<bool, CustomErrors> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Ok(true);
+        }
+
+        match self.mode {
+            Mode::Search => match key.code {
+                KeyCode::Enter => self.mode = Mode::Normal,
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.mode = Mode::Normal;
+                    self.refresh()?;
+                }
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                    self.refresh()?;
+                }
+                KeyCode::Char(c) => {
+                    self.filter.push(c);
+                    self.refresh()?;
+                }
+                _ => (),
+            },
+            Mode::ConfirmDelete => {
+                self.mode = Mode::Normal;
+                if key.code == KeyCode::Char('y') {
+                    self.apply(
+                        |db, link| db.delete_link(link.to_owned()),
+                        "Successfully deleted the link",
+                    )?;
+                } else {
+                    self.message = "Delete cancelled".to_owned();
+                }
+            }
+            Mode::Normal => match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(true),
+                KeyCode::Char('j') | KeyCode::Down => self.move_selection(1)?,
+                KeyCode::Char('k') | KeyCode::Up => self.move_selection(-1)?,
+                KeyCode::PageDown => self.move_selection(10)?,
+                KeyCode::PageUp => self.move_selection(-10)?,
+                KeyCode::Char('g') | KeyCode::Home => self.move_selection(isize::MIN / 2)?,
+                KeyCode::Char('G') | KeyCode::End => self.move_selection(isize::MAX / 2)?,
+                KeyCode::Char('c') => self.apply(
+                    |db, link| db.mark_as_complete(link),
+                    "Successfully marked the link as completed",
+                )?,
+                KeyCode::Char('f') => self.apply(
+                    |db, link| db.mark_as_failed(link),
+                    "Recorded the failed attempt, the link comes back first",
+                )?,
+                KeyCode::Char('s') => self.apply(
+                    |db, link| db.skip_link(link),
+                    "Successfully skipped the link",
+                )?,
+                KeyCode::Char('r') => self.apply(
+                    |db, link| {
+                        db.apply_bulk_action(&BulkAction::Reset, &[link.to_owned()])
+                            .map(|_| ())
+                    },
+                    "Successfully reset the link to incomplete",
+                )?,
+                KeyCode::Char('o') => self.apply(
+                    |_, link| open_in_browser(link),
+                    "Opened the link in the browser",
+                )?,
+                KeyCode::Char('d') if self.selected_link().is_some() => {
+                    self.mode = Mode::ConfirmDelete;
+                }
+                KeyCode::Char('/') => {
+                    self.mode = Mode::Search;
+                    self.message.clear();
+                }
+                _ => (),
+            },
+        };
+
+        Ok(false)
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),
+                Constraint::Min(5),
+                Constraint::Length(1),
+            ])
+            .split(frame.area());
+
+        let search_title = format!(
+            " abhyas | total {} | completed {} | skipped {} | failed {} | showing {} ",
+            self.status.total,
+            self.status.completed,
+            self.status.skipped,
+            self.status.failed,
+            self.links.len()
+        );
+        let search_style = match self.mode {
+            Mode::Search => Style::default().fg(Color::Yellow),
+            _ => Style::default(),
+        };
+        let search = Paragraph::new(format!("/{}", self.filter)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(search_title)
+                .border_style(search_style),
+        );
+        frame.render_widget(search, rows[0]);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+            .split(rows[1]);
+
+        let items: Vec<ListItem> = self
+            .links
+            .iter()
+            .map(|link| {
+                ListItem::new(format!(
+                    "{} [{}] ({})",
+                    link.link, link.state, link.solved_count
+                ))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::ALL).title(" Links "))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, columns[0], &mut self.list_state);
+
+        let detail_lines = match self.selected() {
+            Some(details) => {
+                let state_color = match details.state {
+                    LinkState::Completed => Color::Green,
+                    LinkState::Skipped => Color::Red,
+                    LinkState::Pending => Color::Yellow,
+                    LinkState::Failed => Color::Magenta,
+                };
+                let state =
+                    Span::styled(details.state.to_string(), Style::default().fg(state_color));
+
+                let mut lines = vec![
+                    Line::from(details.link.clone()),
+                    Line::from(format!("title: {}", details.title)),
+                    Line::from(vec![Span::raw("state: "), state]),
+                    Line::from(format!("solved count: {}", details.solved_count)),
+                    Line::from(format!("failed count: {}", details.failed_count)),
+                    Line::from(format!(
+                        "last completed: {}",
+                        details.completed_at.clone().unwrap_or("never".to_owned())
+                    )),
+                    Line::from(format!(
+                        "snoozed until: {}",
+                        details.skipped_until.clone().unwrap_or("-".to_owned())
+                    )),
+                    Line::from(format!("tags: {}", details.tags.join(", "))),
+                    Line::from(""),
+                    Line::from(format!("notes: {}", details.notes)),
+                    Line::from(""),
+                    Line::from("history:"),
+                ];
+                lines.extend(
+                    self.history
+                        .iter()
+                        .map(|(action, at)| Line::from(format!("  {}  {}", at, action))),
+                );
+                lines
+            }
+            None => vec![Line::from("No links matched the search")],
+        };
+        let details = Paragraph::new(detail_lines)
+            .block(Block::default().borders(Borders::ALL).title(" Details "))
+            .wrap(Wrap { trim: false });
+        frame.render_widget(details, columns[1]);
+
+        let footer = match self.mode {
+            Mode::Search => SEARCH_HELP.to_owned(),
+            Mode::ConfirmDelete => "Delete the selected link? (y/n)".to_owned(),
+            Mode::Normal if !self.message.is_empty() => self.message.clone(),
+            Mode::Normal => NORMAL_HELP.to_owned(),
+        };
+        frame.render_widget(Paragraph::new(footer), rows[2]);
+    }
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<(), CustomErrors> {
+    app.refresh()?;
+
+    loop {
+        if let Err(e) = terminal.draw(|frame| app.draw(frame)) {
+            return Err(CustomErrors::Io(
+                "Couldn't draw the terminal interface".to_owned(),
+                e,
+            ));
+        }
+
+        let key = match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(e) => {
+                return Err(CustomErrors::Io(
+                    "Couldn't read the key press".to_owned(),
+                    e,
+                ))
+            }
+        };
+
+        let quit = app.handle_key(key)?;
+        // printing would draw over the interface, failed hooks show up in the message bar
+        let warnings = app.db.take_warnings();
+        match warnings.len() {
+            0 => (),
+            1 => app.message = format!("Warning: {}", warnings[0]),
+            count => app.message = format!("{} Warnings: {}", count, warnings.join(" | ")),
+        };
+        if quit {
+            return Ok(());
+        }
+    }
+}
+
+/// show the full-screen terminal interface until the user quits
+pub fn run_tui(db: &Db) -> Result<(), CustomErrors> {
+    let mut terminal = ratatui::init();
+    let mut app = App::new(db);
+
+    let result = event_loop(&mut terminal, &mut app);
+
+    ratatui::restore();
+    result
+}
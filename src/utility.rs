@@ -1,62 +1,20 @@
+use crate::args::{Cli, Command as CliCommand};
 use crate::cli::show_options;
-use crate::database::Db;
-use crate::CustomErrors;
-use rusqlite::Connection;
-use std::env;
-use std::fs::{self, OpenOptions};
+use crate::commands::{completions_command, run_command};
+use abhyas::config::Config;
+use abhyas::database::Db;
+use abhyas::import::read_links_from_file;
+use abhyas::link::{LinkFilter, LinkSort};
+use abhyas::webhook::Webhooks;
+use abhyas::CustomErrors;
+use std::collections::HashSet;
 use std::io::Write;
-use std::path::Path;
-use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 
-/// create directory to store the db file
-fn create_file() -> Result<PathBuf, CustomErrors> {
-    let cache_dir = match dirs::cache_dir() {
-        Some(value) => value,
-        None => return Err(CustomErrors::CacheDirectoryNotFound),
-    };
-
-    let dir_name = &cache_dir.join("abhyas");
-    match fs::create_dir_all(dir_name) {
-        Ok(_) => (),
-        Err(_) => return Err(CustomErrors::CreateDirectoryFailed),
-    };
-
-    let file_name = &dir_name.join("abhyas.db");
-    match OpenOptions::new().write(true).create(true).open(file_name) {
-        Ok(_) => (),
-        Err(e) => return Err(CustomErrors::FileCreationFailed(e.to_string())),
-    }
-
-    Ok(file_name.to_path_buf())
-}
-
-/// create db connection
-fn create_db_connection() -> Result<Connection, CustomErrors> {
-    let file_name = create_file()?;
-
-    let conn = match Connection::open(file_name) {
-        Ok(value) => value,
-        Err(_) => return Err(CustomErrors::DBConnectionFailed),
-    };
-
-    if conn
-        .execute(
-            "CREATE TABLE IF NOT EXISTS links (
-            link            TEXT PRIMARY KEY,
-            solved_count    INTEGER NOT NULL,
-            is_solved       INTEGER NOT NULL,
-            is_skipped      INTEGER NOT NULL
-        )",
-            (),
-        )
-        .is_err()
-    {
-        return Err(CustomErrors::DBQueryFailed);
-    }
-
-    Ok(conn)
-}
+/// how long a command waits for the webhooks it sent before exiting
+const WEBHOOK_EXIT_WAIT: Duration = Duration::from_secs(2);
 
 pub fn show_green(msg: &str) {
     let mut stdout = StandardStream::stdout(ColorChoice::Always);
@@ -90,44 +48,157 @@ pub fn show_red(msg: &str) {
     }
 }
 
-fn insert_links_from_file() -> Result<Option<Vec<String>>, CustomErrors> {
-    let args: Vec<String> = env::args().collect();
+/// print the message in red to stderr, for errors that must not mix with `--json` output
+pub fn show_error(msg: &str) {
+    let mut stderr = StandardStream::stderr(ColorChoice::Always);
+
+    if stderr
+        .set_color(ColorSpec::new().set_fg(Some(Color::Red)))
+        .is_err()
+    {
+        eprintln!("{}", msg);
+        return;
+    }
 
-    if args.len() == 1 {
-        return Ok(None);
+    if writeln!(&mut stderr, "{}", msg).is_err() {
+        eprintln!("{}", msg);
     }
+    let _ = stderr.reset();
+}
+
+/// open the link with the default browser of the platform
+pub fn open_in_browser(link: &str) -> Result<(), CustomErrors> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
 
-    if args.len() != 3 {
-        return Err(CustomErrors::InsufficientArgs);
+    match command
+        .arg(link)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(_) => Ok(()),
+        Err(e) => Err(CustomErrors::Io(
+            "Couldn't open the link in the browser".to_owned(),
+            e,
+        )),
     }
+}
 
-    if args[1] != "--file" {
-        return Err(CustomErrors::Others(
-            "Invalid flag, missing --file flag".to_owned(),
-        ));
+/// checks that the date is in the YYYY-MM-DD format and exists in the calendar
+pub fn is_valid_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    if parts.len() != 3 || parts[0].len() != 4 || parts[1].len() != 2 || parts[2].len() != 2 {
+        return false;
     }
 
-    let file = Path::new(&args[2]);
-    let data = match fs::read_to_string(file) {
-        Ok(val) => val,
-        Err(e) => return Err(CustomErrors::Others(e.to_string())),
-    };
+    match (
+        parts[0].parse::<u32>(),
+        parts[1].parse::<u32>(),
+        parts[2].parse::<u32>(),
+    ) {
+        (Ok(year), Ok(month), Ok(day)) => day >= 1 && day <= days_in_month(year, month),
+        _ => false,
+    }
+}
+
+/// returns 0 for a month outside 1 to 12
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        _ => 0,
+    }
+}
 
-    let links: Vec<String> = data.lines().map(|link| link.to_owned()).collect();
+/// report what importing the links would change without touching the db
+fn preview_import(db: &Db, links: &[String], collection: Option<&str>) -> Result<(), CustomErrors> {
+    let mut existing: HashSet<String> = db.get_links()?.into_iter().collect();
+    let new_count = links
+        .iter()
+        .filter(|link| existing.insert(link.to_string()))
+        .count();
+    show_green(
+        format!(
+            "Would Insert {} New Links\nWould Skip {} Duplicate Links",
+            new_count,
+            links.len() - new_count
+        )
+        .as_str(),
+    );
 
-    Ok(Some(links))
+    if let Some(collection) = collection {
+        let filter = LinkFilter {
+            collection: Some(collection.to_owned()),
+            ..Default::default()
+        };
+        let mut members: HashSet<String> = db
+            .query_links(&filter, LinkSort::Added, None, 0)?
+            .into_iter()
+            .map(|link| link.link)
+            .collect();
+        let added_count = links
+            .iter()
+            .filter(|link| members.insert(link.to_string()))
+            .count();
+        show_green(
+            format!(
+                "Would Add {} Links To The Collection \"{}\"",
+                added_count, collection
+            )
+            .as_str(),
+        );
+    }
+
+    Ok(())
 }
 
-pub fn run() -> Result<(), CustomErrors> {
-    let _conn: Connection = create_db_connection()?;
+/// print the failures of hooks and webhooks to stderr, so they never mix with --json output
+pub fn show_warnings(db: &Db) {
+    for warning in db.take_warnings() {
+        eprintln!("Warning: {}", warning);
+    }
+}
 
-    let db = Db::new(_conn);
+/// give the webhooks sent in the background a moment before the process exits,
+/// whatever is still running stays queued for the next event or flush
+fn finish(db: &Db) {
+    db.wait_for_webhooks(WEBHOOK_EXIT_WAIT);
+    show_warnings(db);
+}
 
-    let links = insert_links_from_file()?;
+pub fn run(args: Cli) -> Result<(), CustomErrors> {
+    if let Some(CliCommand::Completions(completions)) = &args.command {
+        return completions_command(completions);
+    }
+
+    let config = Config::load_default()?;
+    let db = Db::open_default()?
+        .with_hooks(config.hooks.clone())
+        .with_webhooks(Webhooks::with_default_queue(config.webhooks.clone())?);
 
-    if let Some(links_vec) = links {
+    if let Some(file) = args.file {
+        let links_vec = read_links_from_file(&file)?;
         let links_count = links_vec.len();
-        match db.insert_links_from_file(&links_vec) {
+
+        if args.dry_run {
+            return preview_import(&db, &links_vec, args.collection.as_deref());
+        }
+
+        let inserted = db.insert_links_from_file(&links_vec);
+        show_warnings(&db);
+        match inserted {
             Ok(val) => show_green(
                 format!(
                     "Inserted {} New Links\nSkipped {} Duplicate Links",
@@ -138,9 +209,55 @@ pub fn run() -> Result<(), CustomErrors> {
             ),
             Err(e) => return Err(e),
         };
+
+        if let Some(collection) = args.collection {
+            let added_count = db.add_links_to_collection(&collection, &links_vec)?;
+            show_green(
+                format!(
+                    "Added {} Links To The Collection \"{}\"",
+                    added_count, collection
+                )
+                .as_str(),
+            );
+        }
     };
 
+    if let Some(command) = args.command {
+        let result = run_command(&db, &config, command);
+        finish(&db);
+        return result;
+    }
+
     loop {
-        show_options(&db)?;
+        let result = show_options(&db, &config);
+        match result {
+            Ok(_) => show_warnings(&db),
+            Err(_) => finish(&db),
+        };
+        result?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_calendar_dates() {
+        assert!(is_valid_date("2026-09-01"));
+        assert!(is_valid_date("2026-01-31"));
+        assert!(is_valid_date("2024-02-29"));
+        assert!(is_valid_date("2000-02-29"));
+    }
+
+    #[test]
+    fn rejects_impossible_dates() {
+        assert!(!is_valid_date("2026-02-31"));
+        assert!(!is_valid_date("2026-02-29"));
+        assert!(!is_valid_date("1900-02-29"));
+        assert!(!is_valid_date("2026-04-31"));
+        assert!(!is_valid_date("2026-13-01"));
+        assert!(!is_valid_date("2026-00-10"));
+        assert!(!is_valid_date("2026-9-1"));
     }
 }
@@ -0,0 +1,688 @@
+use crate::hooks::HookEvent;
+use crate::CustomErrors;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// file in the abhyas cache directory holding the deliveries that are yet to go out, one per line
+pub const QUEUE_FILE: &str = "webhook-queue.jsonl";
+
+/// streak lengths in days that send a `streak` event
+pub const STREAK_MILESTONES: [i32; 9] = [3, 7, 14, 30, 50, 100, 200, 365, 1000];
+
+/// how often `wait` checks whether the background deliveries are done
+const WAIT_POLL: Duration = Duration::from_millis(20);
+
+/// seconds before the first retry of a failed delivery, doubled with every failure
+const RETRY_DELAY_SECS: u64 = 30;
+
+/// the retry delay stops doubling at six hours
+const MAX_RETRY_DELAY_SECS: u64 = 6 * 60 * 60;
+
+/// makes the ids of deliveries queued by the same process within a nanosecond unique
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// events a webhook can subscribe to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookEvent {
+    Add,
+    Complete,
+    Skip,
+    /// the daily streak reached one of `STREAK_MILESTONES`
+    Streak,
+}
+
+impl WebhookEvent {
+    /// the webhook event sent along with a hook event, if any
+    pub fn from_hook(event: HookEvent) -> Option<Self> {
+        match event {
+            HookEvent::Add => Some(WebhookEvent::Add),
+            HookEvent::Complete => Some(WebhookEvent::Complete),
+            HookEvent::Skip => Some(WebhookEvent::Skip),
+            HookEvent::Delete | HookEvent::Import => None,
+        }
+    }
+}
+
+/// an url that gets a json POST for every event it subscribed to,
+/// one `[[webhooks]]` entry of the config
+#[derive(Clone, Debug, Deserialize)]
+pub struct Webhook {
+    pub url: String,
+    /// events to send, every event when empty
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    /// failed attempts after which an event is dropped
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    /// seconds to wait for the endpoint to answer
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_max_attempts() -> u32 {
+    20
+}
+
+fn default_timeout_secs() -> u64 {
+    5
+}
+
+/// an event waiting in the queue file for its url
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Delivery {
+    /// tells the delivery apart from the ones queued while it was being sent
+    id: String,
+    url: String,
+    body: String,
+    /// failed attempts so far
+    attempts: u32,
+    /// unix time in seconds before which the delivery isn't retried
+    #[serde(default)]
+    retry_at: u64,
+}
+
+/// what happened to the deliveries of a flush
+#[derive(Clone, Debug, Default)]
+pub struct DeliverySummary {
+    pub delivered: usize,
+    /// still in the queue, to be retried once their delay is over
+    pub queued: usize,
+    /// rejected by the endpoint with a client error or out of attempts
+    pub dropped: usize,
+    /// why deliveries failed, one message per failure
+    pub errors: Vec<String>,
+}
+
+/// outcome of a single attempt
+enum Attempt {
+    Delivered,
+    /// the endpoint was unreachable or had a server error
+    Failed(String),
+    /// the endpoint rejected the event, retrying won't help
+    Rejected(String),
+}
+
+/// the configured webhooks along with the queue of deliveries that are yet to go out
+///
+/// events are only appended to the queue when links change, the queue is sent from
+/// a background thread so a slow or unreachable endpoint never holds up a change
+#[derive(Clone, Debug, Default)]
+pub struct Webhooks {
+    webhooks: Vec<Webhook>,
+    queue: PathBuf,
+    /// failures of the background deliveries
+    warnings: Arc<Mutex<Vec<String>>>,
+    workers: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl Webhooks {
+    /// keep the deliveries in the given queue file
+    pub fn new(webhooks: Vec<Webhook>, queue: PathBuf) -> Self {
+        Webhooks {
+            webhooks,
+            queue,
+            ..Webhooks::default()
+        }
+    }
+
+    /// keep the deliveries in `webhook-queue.jsonl` of the abhyas cache directory
+    pub fn with_default_queue(webhooks: Vec<Webhook>) -> Result<Self, CustomErrors> {
+        match dirs::cache_dir() {
+            Some(dir) => Ok(Webhooks::new(webhooks, dir.join("abhyas").join(QUEUE_FILE))),
+            None => Err(CustomErrors::CacheDirectoryNotFound),
+        }
+    }
+
+    /// returns true when any webhook subscribed to the event
+    pub fn wants(&self, event: WebhookEvent) -> bool {
+        self.webhooks
+            .iter()
+            .any(|webhook| webhook.events.is_empty() || webhook.events.contains(&event))
+    }
+
+    /// queue the json body for every webhook subscribed to the event and send the
+    /// queue from a background thread, see `wait` and `take_warnings`
+    pub fn send(&self, event: WebhookEvent, body: &serde_json::Value) -> Result<(), CustomErrors> {
+        if self.enqueue(event, body)? == 0 {
+            return Ok(());
+        }
+
+        let webhooks = self.clone();
+        let worker = thread::spawn(move || match webhooks.flush() {
+            Ok(summary) => webhooks.warnings_mut().extend(summary.errors),
+            Err(e) => webhooks.warnings_mut().push(e.to_string()),
+        });
+
+        let mut workers = match self.workers.lock() {
+            Ok(val) => val,
+            Err(e) => e.into_inner(),
+        };
+        workers.retain(|worker| !worker.is_finished());
+        workers.push(worker);
+
+        Ok(())
+    }
+
+    /// append the json body to the queue for every webhook subscribed to the event,
+    /// returns the number of deliveries queued
+    pub fn enqueue(
+        &self,
+        event: WebhookEvent,
+        body: &serde_json::Value,
+    ) -> Result<usize, CustomErrors> {
+        let deliveries: Vec<Delivery> = self
+            .webhooks
+            .iter()
+            .filter(|webhook| webhook.events.is_empty() || webhook.events.contains(&event))
+            .map(|webhook| Delivery {
+                id: delivery_id(),
+                url: webhook.url.clone(),
+                body: body.to_string(),
+                attempts: 0,
+                retry_at: 0,
+            })
+            .collect();
+        if deliveries.is_empty() {
+            return Ok(0);
+        }
+
+        let _lock = self.lock("lock")?;
+        let mut queue = self.read_queue()?;
+        queue.extend(deliveries.iter().cloned());
+        self.write_queue(&queue)?;
+
+        Ok(deliveries.len())
+    }
+
+    /// send every queued delivery that is due once, in order, a delivery that fails
+    /// stays queued along with the later ones of its url and is retried after a delay
+    /// that doubles with every failure, until it runs out of attempts
+    pub fn flush(&self) -> Result<DeliverySummary, CustomErrors> {
+        self.flush_queue(false)
+    }
+
+    /// send every queued delivery once, in order, without waiting for the retry delays
+    pub fn flush_now(&self) -> Result<DeliverySummary, CustomErrors> {
+        self.flush_queue(true)
+    }
+
+    fn flush_queue(&self, retry_now: bool) -> Result<DeliverySummary, CustomErrors> {
+        // a second flush, of this or another process, waits and then sends what is left
+        let _flush_lock = self.lock("flush.lock")?;
+
+        let pending = {
+            let _lock = self.lock("lock")?;
+            self.read_queue()?
+        };
+
+        // every url gets its own thread, an unreachable endpoint doesn't hold up the others,
+        // a url whose first delivery is waiting for its retry delay is left alone so the
+        // endpoint still gets the events in order
+        let now = unix_now();
+        let mut by_url: Vec<(&str, Vec<&Delivery>)> = vec![];
+        for delivery in &pending {
+            match by_url.iter_mut().find(|(url, _)| *url == delivery.url) {
+                Some((_, deliveries)) => deliveries.push(delivery),
+                None => by_url.push((&delivery.url, vec![delivery])),
+            };
+        }
+        by_url.retain(|(_, deliveries)| retry_now || deliveries[0].retry_at <= now);
+        let summaries: Vec<DeliverySummary> = thread::scope(|scope| {
+            let workers: Vec<_> = by_url
+                .into_iter()
+                .map(|(_, deliveries)| scope.spawn(move || self.post_in_order(&deliveries)))
+                .collect();
+            workers
+                .into_iter()
+                .filter_map(|worker| worker.join().ok())
+                .collect()
+        });
+
+        let mut summary = DeliverySummary::default();
+        for part in summaries {
+            summary.delivered += part.delivered;
+            summary.dropped += part.dropped;
+            summary.errors.extend(part.errors);
+        }
+        summary.queued = {
+            let _lock = self.lock("lock")?;
+            self.read_queue()?.len()
+        };
+
+        Ok(summary)
+    }
+
+    /// wait at most `timeout` for the background deliveries, returns false when some
+    /// are still running, their events stay queued if the process exits now
+    pub fn wait(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let finished = match self.workers.lock() {
+                Ok(workers) => workers.iter().all(|worker| worker.is_finished()),
+                Err(_) => true,
+            };
+            if finished {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(WAIT_POLL);
+        }
+    }
+
+    /// returns and clears the failures of the background deliveries
+    pub fn take_warnings(&self) -> Vec<String> {
+        self.warnings_mut().drain(..).collect()
+    }
+
+    fn warnings_mut(&self) -> std::sync::MutexGuard<'_, Vec<String>> {
+        match self.warnings.lock() {
+            Ok(val) => val,
+            Err(e) => e.into_inner(),
+        }
+    }
+
+    fn webhook(&self, url: &str) -> Option<&Webhook> {
+        self.webhooks.iter().find(|webhook| webhook.url == url)
+    }
+
+    /// queued deliveries of a webhook that was removed from the config use the default
+    fn max_attempts(&self, url: &str) -> u32 {
+        self.webhook(url)
+            .map_or_else(default_max_attempts, |webhook| webhook.max_attempts)
+    }
+
+    /// send the deliveries of a single url until one fails, the later ones wait in
+    /// the queue so the endpoint still gets the events in order
+    fn post_in_order(&self, deliveries: &[&Delivery]) -> DeliverySummary {
+        let mut summary = DeliverySummary::default();
+        for delivery in deliveries {
+            let attempt = self.post(delivery);
+            let failed = matches!(attempt, Attempt::Failed(_));
+
+            // the queue is updated right away, a process that exits in the middle
+            // of a flush doesn't send the delivered events again
+            let stays = match self.settle(delivery, failed) {
+                Ok(val) => val,
+                Err(e) => {
+                    summary.errors.push(e.to_string());
+                    break;
+                }
+            };
+
+            match attempt {
+                Attempt::Delivered => summary.delivered += 1,
+                Attempt::Rejected(e) => {
+                    summary.dropped += 1;
+                    summary.errors.push(e);
+                }
+                Attempt::Failed(e) if stays => summary.errors.push(format!(
+                    "Couldn't deliver the event to {}, it stays queued: {}",
+                    delivery.url, e
+                )),
+                Attempt::Failed(e) => {
+                    summary.dropped += 1;
+                    summary.errors.push(format!(
+                        "Dropped the event for {} after {} failed attempts: {}",
+                        delivery.url,
+                        self.max_attempts(&delivery.url),
+                        e
+                    ));
+                }
+            };
+            if failed {
+                break;
+            }
+        }
+
+        summary
+    }
+
+    /// remove the delivery from the queue unless it failed with attempts left, in which
+    /// case its retry is put off, returns true when it stays queued
+    fn settle(&self, delivery: &Delivery, failed: bool) -> Result<bool, CustomErrors> {
+        let max_attempts = self.max_attempts(&delivery.url);
+        let _lock = self.lock("lock")?;
+
+        let mut stays = false;
+        let mut queue = self.read_queue()?;
+        queue.retain_mut(|queued| {
+            if queued.id != delivery.id {
+                return true;
+            }
+            if failed {
+                queued.attempts += 1;
+                queued.retry_at = unix_now() + retry_delay_secs(queued.attempts);
+                stays = queued.attempts < max_attempts;
+            }
+            stays
+        });
+        self.write_queue(&queue)?;
+
+        Ok(stays)
+    }
+
+    fn post(&self, delivery: &Delivery) -> Attempt {
+        let timeout_secs = self
+            .webhook(&delivery.url)
+            .map_or_else(default_timeout_secs, |webhook| webhook.timeout_secs);
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(timeout_secs))
+            .build();
+
+        match agent
+            .post(&delivery.url)
+            .set("Content-Type", "application/json")
+            .send_string(&delivery.body)
+        {
+            Ok(_) => Attempt::Delivered,
+            Err(ureq::Error::Status(code, _)) if code < 500 && code != 408 && code != 429 => {
+                Attempt::Rejected(format!(
+                    "The webhook {} rejected the event with status {}, it was dropped",
+                    delivery.url, code
+                ))
+            }
+            Err(e) => Attempt::Failed(e.to_string()),
+        }
+    }
+
+    /// take an advisory lock on a file next to the queue, released when the file is dropped
+    fn lock(&self, extension: &str) -> Result<File, CustomErrors> {
+        let path = self.queue.with_extension(extension);
+        let file = match OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+        {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(CustomErrors::Io(
+                    format!("Couldn't open the webhook lock {}", path.display()),
+                    e,
+                ))
+            }
+        };
+
+        match file.lock() {
+            Ok(_) => Ok(file),
+            Err(e) => Err(CustomErrors::Io(
+                format!("Couldn't lock the webhook queue {}", path.display()),
+                e,
+            )),
+        }
+    }
+
+    fn read_queue(&self) -> Result<Vec<Delivery>, CustomErrors> {
+        let data = match fs::read_to_string(&self.queue) {
+            Ok(val) => val,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => {
+                return Err(CustomErrors::Io(
+                    format!("Couldn't read the webhook queue {}", self.queue.display()),
+                    e,
+                ))
+            }
+        };
+
+        data.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| match serde_json::from_str(line) {
+                Ok(val) => Ok(val),
+                Err(e) => Err(CustomErrors::InvalidInput(format!(
+                    "Invalid entry in the webhook queue {}: {}",
+                    self.queue.display(),
+                    e
+                ))),
+            })
+            .collect()
+    }
+
+    /// replace the queue file through a rename so a reader never sees half of it
+    fn write_queue(&self, deliveries: &[Delivery]) -> Result<(), CustomErrors> {
+        if deliveries.is_empty() {
+            return match fs::remove_file(&self.queue) {
+                Err(e) if e.kind() != ErrorKind::NotFound => Err(CustomErrors::Io(
+                    format!("Couldn't remove the webhook queue {}", self.queue.display()),
+                    e,
+                )),
+                _ => Ok(()),
+            };
+        }
+
+        let mut output = String::new();
+        for delivery in deliveries {
+            match serde_json::to_string(delivery) {
+                Ok(line) => output.push_str(&line),
+                Err(e) => {
+                    return Err(CustomErrors::Context(
+                        "Couldn't serialize the webhook queue".to_owned(),
+                        Box::new(e),
+                    ))
+                }
+            };
+            output.push('\n');
+        }
+
+        let temp = self.queue.with_extension("jsonl.tmp");
+        match write_file(&temp, &output).and_then(|_| fs::rename(&temp, &self.queue)) {
+            Ok(_) => Ok(()),
+            Err(e) => Err(CustomErrors::Io(
+                format!("Couldn't write the webhook queue {}", self.queue.display()),
+                e,
+            )),
+        }
+    }
+}
+
+fn write_file(path: &Path, data: &str) -> std::io::Result<()> {
+    let mut file = File::create(path)?;
+    file.write_all(data.as_bytes())?;
+    file.sync_all()
+}
+
+/// seconds to wait before retrying a delivery that failed this many times
+fn retry_delay_secs(attempts: u32) -> u64 {
+    RETRY_DELAY_SECS
+        .checked_shl(attempts.saturating_sub(1))
+        .map_or(MAX_RETRY_DELAY_SECS, |delay| {
+            delay.min(MAX_RETRY_DELAY_SECS)
+        })
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+fn delivery_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos());
+    format!(
+        "{}-{}-{}",
+        nanos,
+        process::id(),
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::env;
+    use std::sync::atomic::AtomicU16;
+    use std::sync::mpsc::{self, Receiver};
+
+    /// a stand-in endpoint on an ephemeral port answering with the status in `status`,
+    /// returns its url along with the path and body of every request it got
+    fn serve(status: Arc<AtomicU16>) -> (String, Receiver<(String, String)>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").expect("the test server starts");
+        let port = server.server_addr().to_ip().expect("an ip address").port();
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = String::new();
+                let _ = request.as_reader().read_to_string(&mut body);
+                let _ = sender.send((request.url().to_owned(), body));
+                let response = tiny_http::Response::empty(status.load(Ordering::SeqCst));
+                let _ = request.respond(response);
+            }
+        });
+
+        (format!("http://127.0.0.1:{}", port), receiver)
+    }
+
+    fn queue_file(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("abhyas-webhook-{}-{}", process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("the test directory is created");
+        dir.join(QUEUE_FILE)
+    }
+
+    fn webhook(url: String, max_attempts: u32) -> Webhook {
+        Webhook {
+            url,
+            events: vec![],
+            max_attempts,
+            timeout_secs: 5,
+        }
+    }
+
+    fn queued(webhooks: &Webhooks) -> usize {
+        webhooks.read_queue().expect("the queue is readable").len()
+    }
+
+    #[test]
+    fn delivers_the_event() -> Result<(), CustomErrors> {
+        let (url, requests) = serve(Arc::new(AtomicU16::new(200)));
+        let queue = queue_file("deliver");
+        let webhooks = Webhooks::new(vec![webhook(format!("{}/hook", url), 20)], queue.clone());
+
+        webhooks.enqueue(WebhookEvent::Add, &json!({ "event": "add" }))?;
+        let summary = webhooks.flush()?;
+
+        assert_eq!((summary.delivered, summary.queued), (1, 0));
+        assert_eq!(
+            requests.recv_timeout(Duration::from_secs(5)).ok(),
+            Some(("/hook".to_owned(), r#"{"event":"add"}"#.to_owned()))
+        );
+        assert!(!queue.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn drops_rejected_events() -> Result<(), CustomErrors> {
+        let (url, _requests) = serve(Arc::new(AtomicU16::new(404)));
+        let webhooks = Webhooks::new(vec![webhook(url, 20)], queue_file("reject"));
+
+        webhooks.enqueue(WebhookEvent::Skip, &json!({ "event": "skip" }))?;
+        let summary = webhooks.flush()?;
+
+        assert_eq!(
+            (summary.delivered, summary.dropped, summary.queued),
+            (0, 1, 0)
+        );
+        assert_eq!(queued(&webhooks), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn queues_until_the_endpoint_is_back() -> Result<(), CustomErrors> {
+        let status = Arc::new(AtomicU16::new(503));
+        let (url, requests) = serve(status.clone());
+        let webhooks = Webhooks::new(vec![webhook(url, 20)], queue_file("queue"));
+
+        webhooks.enqueue(WebhookEvent::Add, &json!({ "n": 1 }))?;
+        webhooks.enqueue(WebhookEvent::Complete, &json!({ "n": 2 }))?;
+        let summary = webhooks.flush()?;
+
+        // the later event waits behind the failed one
+        assert_eq!((summary.delivered, summary.queued), (0, 2));
+        assert_eq!(requests.try_iter().count(), 1);
+
+        status.store(200, Ordering::SeqCst);
+        let summary = webhooks.flush_now()?;
+
+        assert_eq!((summary.delivered, summary.queued), (2, 0));
+        let bodies: Vec<String> = requests.try_iter().map(|(_, body)| body).collect();
+        assert_eq!(bodies, vec![r#"{"n":1}"#, r#"{"n":2}"#]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn waits_for_the_retry_delay() -> Result<(), CustomErrors> {
+        let (url, requests) = serve(Arc::new(AtomicU16::new(503)));
+        let webhooks = Webhooks::new(vec![webhook(url, 20)], queue_file("delay"));
+
+        webhooks.enqueue(WebhookEvent::Add, &json!({}))?;
+        webhooks.flush()?;
+        let summary = webhooks.flush()?;
+
+        // the second flush comes before the delay is over and leaves the event alone
+        assert_eq!((summary.delivered, summary.queued), (0, 1));
+        assert!(summary.errors.is_empty());
+        assert_eq!(requests.try_iter().count(), 1);
+        assert_eq!(webhooks.read_queue()?[0].attempts, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn doubles_the_retry_delay() {
+        assert_eq!(retry_delay_secs(1), 30);
+        assert_eq!(retry_delay_secs(2), 60);
+        assert_eq!(retry_delay_secs(10), 15360);
+        assert_eq!(retry_delay_secs(11), MAX_RETRY_DELAY_SECS);
+        assert_eq!(retry_delay_secs(100), MAX_RETRY_DELAY_SECS);
+    }
+
+    #[test]
+    fn drops_events_out_of_attempts() -> Result<(), CustomErrors> {
+        let (url, _requests) = serve(Arc::new(AtomicU16::new(500)));
+        let webhooks = Webhooks::new(vec![webhook(url, 2)], queue_file("attempts"));
+
+        webhooks.enqueue(WebhookEvent::Add, &json!({}))?;
+        assert_eq!(webhooks.flush()?.queued, 1);
+        let summary = webhooks.flush_now()?;
+
+        assert_eq!((summary.dropped, summary.queued), (1, 0));
+        assert_eq!(summary.errors.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sends_in_the_background() -> Result<(), CustomErrors> {
+        let (url, requests) = serve(Arc::new(AtomicU16::new(200)));
+        let mut only_streak = webhook(url, 20);
+        only_streak.events = vec![WebhookEvent::Streak];
+        let webhooks = Webhooks::new(vec![only_streak], queue_file("background"));
+
+        webhooks.send(WebhookEvent::Add, &json!({ "event": "add" }))?;
+        webhooks.send(WebhookEvent::Streak, &json!({ "days": 3 }))?;
+
+        assert!(webhooks.wait(Duration::from_secs(5)));
+        assert!(webhooks.take_warnings().is_empty());
+        let bodies: Vec<String> = requests.try_iter().map(|(_, body)| body).collect();
+        assert_eq!(bodies, vec![r#"{"days":3}"#]);
+        assert_eq!(queued(&webhooks), 0);
+
+        Ok(())
+    }
+}